@@ -0,0 +1,237 @@
+// Błędy programu UNO - każda ścieżka odrzucenia ma własny, stały kod
+
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Błędy zwracane przez program jako `ProgramError::Custom(kod)`.
+///
+/// Numeracja jest stała - nowe warianty dopisujemy wyłącznie na końcu,
+/// żeby klienci dekodujący kody nie musieli się aktualizować.
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum UnoError {
+    /// Przekazane konto nie jest System Programem
+    #[error("Invalid system program account")]
+    InvalidSystemProgram = 0,
+    /// Liczba graczy poza dozwolonym zakresem
+    #[error("Invalid max players")]
+    InvalidMaxPlayers = 1,
+    /// Wpisowe poza dozwolonym zakresem
+    #[error("Invalid entry fee")]
+    InvalidEntryFee = 2,
+    /// Numer slotu pokoju poza zakresem (pokoje adresowane slotem - zastąpione licznikiem twórcy)
+    #[error("Invalid room slot")]
+    InvalidRoomSlot = 3,
+    /// Konto pokoju nie jest oczekiwanym PDA
+    #[error("Invalid room address")]
+    InvalidRoomAddress = 4,
+    /// Dane konta nie są poprawnym pokojem
+    #[error("Invalid room data")]
+    InvalidRoomData = 5,
+    /// Pokój nie oczekuje już na graczy
+    #[error("Room is not waiting for players")]
+    RoomNotWaiting = 6,
+    /// Gracz już siedzi w pokoju
+    #[error("Player already joined")]
+    PlayerAlreadyJoined = 7,
+    /// Pokój jest pełny
+    #[error("Room is full")]
+    RoomFull = 8,
+    /// Podpisujący nie jest graczem w pokoju
+    #[error("Signer is not a player in this room")]
+    NotAPlayer = 9,
+    /// Za mało graczy, żeby rozpocząć grę
+    #[error("Not enough players")]
+    NotEnoughPlayers = 10,
+    /// Gra nie jest w toku
+    #[error("Game is not in progress")]
+    GameNotInProgress = 11,
+    /// Wskazany zwycięzca nie jest graczem w pokoju
+    #[error("Winner is not a player in this room")]
+    WinnerNotAPlayer = 12,
+    /// Nieprawidłowy portfel platformy
+    #[error("Invalid platform fee account")]
+    InvalidPlatformWallet = 13,
+    /// Gra nie została zakończona
+    #[error("Game is not completed")]
+    GameNotCompleted = 14,
+    /// Odbierający nie jest zwycięzcą
+    #[error("Claimer is not the winner")]
+    NotTheWinner = 15,
+    /// Nagroda została już odebrana
+    #[error("Prize already claimed")]
+    PrizeAlreadyClaimed = 16,
+    /// Konto pokoju nie ma wystarczających środków na wypłatę
+    #[error("Insufficient funds in the prize pool")]
+    InsufficientPrizePool = 17,
+    /// Podpisujący nie jest twórcą pokoju
+    #[error("Signer is not the room creator")]
+    NotRoomCreator = 18,
+    /// Konto do zwrotu wpisowego nie odpowiada graczowi
+    #[error("Refund account does not match the player")]
+    RefundAccountMismatch = 19,
+    /// Przepełnienie przy obliczeniach na lamportach
+    #[error("Arithmetic overflow")]
+    ArithmeticOverflow = 20,
+    /// Wynik nie został podpisany przez serwer gry ani jednogłośnie przez graczy
+    #[error("Result must be signed by the game server or by all players")]
+    ResultNotApproved = 21,
+    /// Konto konfiguracji nie jest poprawnym PDA konfiguracji programu
+    #[error("Invalid config account")]
+    InvalidConfigAccount = 22,
+    /// Konfiguracja programu już istnieje
+    #[error("Config already initialized")]
+    ConfigAlreadyInitialized = 23,
+    /// Podpisujący nie jest administratorem konfiguracji
+    #[error("Signer is not the config admin")]
+    NotConfigAdmin = 24,
+    /// Niespójne parametry konfiguracji
+    #[error("Invalid config parameters")]
+    InvalidConfigParams = 25,
+    /// Termin porzucenia gry jeszcze nie minął
+    #[error("Game has not been abandoned yet")]
+    GameNotAbandoned = 26,
+    /// Twórca pokoju nie może go opuścić
+    #[error("Room creator cannot leave the room")]
+    CreatorCannotLeave = 27,
+    /// Dyskryminator konta nie odpowiada oczekiwanemu typowi
+    #[error("Account data does not match the expected account type")]
+    InvalidAccountType = 28,
+    /// Nieobsługiwana wersja układu danych konta
+    #[error("Unsupported account layout version")]
+    UnsupportedAccountVersion = 29,
+    /// Zapis ruchów zawiera ruch niezgodny z zasadami gry
+    #[error("Invalid game transcript")]
+    InvalidTranscript = 30,
+    /// Odtworzona rozgrywka nie kończy się wygraną żadnego gracza
+    #[error("Transcript does not end with a winner")]
+    TranscriptIncomplete = 31,
+    /// Gracz już złożył zobowiązanie do ziarna talii
+    #[error("Seed already committed")]
+    SeedAlreadyCommitted = 32,
+    /// Nie wszyscy gracze złożyli zobowiązania do ziaren
+    #[error("Not all players have committed their seeds")]
+    SeedsNotCommitted = 33,
+    /// Ujawnione ziarno nie odpowiada zobowiązaniu
+    #[error("Revealed seed does not match the commitment")]
+    InvalidSeedReveal = 34,
+    /// Gracz już ujawnił swoje ziarno
+    #[error("Seed already revealed")]
+    SeedAlreadyRevealed = 35,
+    /// Nie wszyscy gracze ujawnili swoje ziarna
+    #[error("Not all players have revealed their seeds")]
+    SeedsNotRevealed = 36,
+    /// Trwa ujawnianie ziaren - skład pokoju jest zamrożony
+    #[error("Seed reveal already in progress")]
+    SeedRevealInProgress = 37,
+    /// Przekazane konto nie jest poprawnym SlotHashes sysvar
+    #[error("Invalid SlotHashes sysvar account")]
+    InvalidSlotHashes = 38,
+    /// Konto tokenowe nie należy do oczekiwanego portfela lub ma inny mint
+    #[error("Invalid token account")]
+    InvalidTokenAccount = 39,
+    /// Konto skarbca nie jest oczekiwanym PDA
+    #[error("Invalid room vault address")]
+    InvalidVault = 40,
+    /// Przekazane konto nie jest SPL Token programem
+    #[error("Invalid token program account")]
+    InvalidTokenProgram = 41,
+    /// Tabela wypłat nie sumuje się do 100% lub ma więcej miejsc niż graczy
+    #[error("Invalid payout table")]
+    InvalidPayoutTable = 42,
+    /// Kolejność miejsc nie obejmuje każdego gracza dokładnie raz
+    #[error("Invalid finishing order")]
+    InvalidFinishingOrder = 43,
+    /// Pokój wypłaca kilka miejsc - wynik musi zawierać pełną kolejność
+    #[error("Finishing order required for multi-place payouts")]
+    FinishingOrderRequired = 44,
+    /// Konto licznika pokojów nie jest PDA twórcy lub ma niepoprawne dane
+    #[error("Invalid creator state account")]
+    InvalidCreatorState = 45,
+    /// Nonce pokoju nie jest kolejnym nonce z licznika twórcy
+    #[error("Room nonce does not match the creator's next room nonce")]
+    StaleRoomNonce = 46,
+    /// Lista zaproszonych graczy jest pusta, zbyt długa lub zawiera powtórzenia
+    #[error("Invalid room access settings")]
+    InvalidRoomAccess = 47,
+    /// Gracza nie ma na liście zaproszonych do pokoju prywatnego
+    #[error("Player is not invited to this private room")]
+    NotInvited = 48,
+    /// Brak sekretu zaproszenia lub nie odpowiada on hashowi zapisanemu w pokoju
+    #[error("Invalid invite secret")]
+    InvalidInviteSecret = 49,
+    /// Za mało graczy zgłosiło gotowość do rozpoczęcia gry
+    #[error("Not enough players are ready to start")]
+    PlayersNotReady = 50,
+    /// Kworum gotowości spoza zakresu 2..=max_players
+    #[error("Invalid ready quorum")]
+    InvalidReadyQuorum = 51,
+    /// Operacja niedostępna w pokoju startującym automatycznie po zapełnieniu
+    #[error("Not available in auto-start rooms")]
+    AutoStartRoom = 52,
+    /// Gracz został zbanowany w tym pokoju
+    #[error("Player is banned from this room")]
+    PlayerBanned = 53,
+    /// Lista zbanowanych graczy pokoju jest pełna
+    #[error("Room ban list is full")]
+    BanListFull = 54,
+    /// Podpisujący nie jest gospodarzem pokoju
+    #[error("Signer is not the room host")]
+    NotRoomHost = 55,
+    /// Gospodarz był niedawno aktywny - gracze nie mogą jeszcze przejąć jego roli
+    #[error("Room host is still active")]
+    HostStillActive = 56,
+    /// Gracz poddał grę - nie może wygrać ani poddać jej ponownie
+    #[error("Player has forfeited the game")]
+    PlayerForfeited = 57,
+    /// Konto ProgramData nie należy do tego programu lub program nie jest aktualizowalny
+    #[error("Invalid program data account")]
+    InvalidProgramData = 58,
+    /// Podpisujący nie jest upgrade authority programu
+    #[error("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority = 59,
+}
+
+impl From<UnoError> for ProgramError {
+    fn from(e: UnoError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for UnoError {
+    fn type_of() -> &'static str {
+        "UnoError"
+    }
+}
+
+impl PrintProgramError for UnoError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("UnoError: {}", self);
+    }
+}
+
+impl UnoError {
+    /// Dekoduje kod `ProgramError::Custom` z powrotem na wariant błędu
+    pub fn from_code(code: u32) -> Option<Self> {
+        num_traits::FromPrimitive::from_u32(code)
+    }
+
+    /// Dekoduje błąd programu zwrócony przez runtime (np. w kliencie lub testach)
+    pub fn decode(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,525 @@
+// Budowanie instrukcji programu UNO dla klientów w Rust (boty, backend)
+//
+// Kolejność kont odpowiada dokumentacji wariantów `UnoInstruction`,
+// więc klienci nie muszą składać `AccountMeta` ręcznie.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+
+use crate::rules::{encode_moves, Move};
+use crate::uno_program::{
+    seed_commitment, ConfigParams, CreatorState, GameRoom, RoomAccess, UnoInstruction, CONFIG_SEED, ROOM_SEED,
+};
+
+/// Adres PDA pokoju `[b"uno_game", twórca, nonce]` wraz z bumpem
+///
+/// Pokoje twórcy mają kolejne nonce od zera do `CreatorState::next_room_nonce`.
+pub fn room_address(program_id: &Pubkey, creator: &Pubkey, room_nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROOM_SEED, creator.as_ref(), &room_nonce.to_le_bytes()], program_id)
+}
+
+/// Adres PDA pokoju utworzonego przed licznikiem twórcy `[b"uno_game", twórca, slot]`
+pub fn legacy_room_address(program_id: &Pubkey, creator: &Pubkey, room_slot: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROOM_SEED, creator.as_ref(), &[room_slot]], program_id)
+}
+
+/// Adres PDA licznika pokojów twórcy
+pub fn creator_state_address(program_id: &Pubkey, creator: &Pubkey) -> Pubkey {
+    CreatorState::address(program_id, creator).0
+}
+
+/// Adres konta ProgramData programu (BPF Loader Upgradeable) z jego upgrade authority
+pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// Adres PDA globalnej konfiguracji programu wraz z bumpem
+pub fn config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+// Serializacja do `Vec` nie może się nie powieść, więc buildery zwracają gotową instrukcję
+fn build(program_id: &Pubkey, data: UnoInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    let data = data.try_to_vec().expect("serializing UnoInstruction into Vec");
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+/// Opcje pokoju wspólne dla `create_room` i `create_token_room`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomOptions {
+    /// Udziały kolejnych miejsc w puli, np. `[7_000, 3_000]` = 70/30 (pusta = całość dla zwycięzcy)
+    pub payout_bps: Vec<u16>,
+    /// Pokój publiczny, lista zaproszonych graczy lub hash sekretu zaproszenia (`invite_hash`)
+    pub access: RoomAccess,
+    /// Gra startuje przy dołączeniu ostatniego gracza (bez `StartGame`) - talię wyznacza wtedy
+    /// tylko hash slotu, bo pokój z auto-startem pomija commit-reveal ziaren graczy
+    pub auto_start: bool,
+}
+
+impl Default for RoomOptions {
+    fn default() -> Self {
+        Self { payout_bps: Vec::new(), access: RoomAccess::Public, auto_start: false }
+    }
+}
+
+/// `CreateRoom` - wyznacza PDA pokoju i uzupełnia System Program, Rent sysvar, konfigurację
+/// oraz licznik twórcy (`room_nonce` to bieżący `next_room_nonce`, 0 dla pierwszego pokoju)
+pub fn create_room(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    max_players: u8,
+    entry_fee_lamports: u64,
+    room_nonce: u64,
+    options: RoomOptions,
+) -> Instruction {
+    build_create_room(program_id, creator, max_players, entry_fee_lamports, room_nonce, None, options)
+}
+
+// Wspólna część `CreateRoom` - konta tokenowe dokłada `create_token_room`
+fn build_create_room(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    max_players: u8,
+    entry_fee_lamports: u64,
+    room_nonce: u64,
+    mint: Option<Pubkey>,
+    options: RoomOptions,
+) -> Instruction {
+    let (room, _) = room_address(program_id, creator, room_nonce);
+    build(
+        program_id,
+        UnoInstruction::CreateRoom {
+            max_players,
+            entry_fee_lamports,
+            room_nonce,
+            mint,
+            payout_bps: options.payout_bps,
+            access: options.access,
+            auto_start: options.auto_start,
+        },
+        vec![
+            AccountMeta::new(*creator, true),
+            AccountMeta::new(room, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(config_address(program_id).0, false),
+            AccountMeta::new(creator_state_address(program_id, creator), false),
+        ],
+    )
+}
+
+/// Dokłada SlotHashes sysvar do `JoinRoom` - wymagany w pokoju z auto-startem
+pub fn with_slot_hashes(mut instruction: Instruction) -> Instruction {
+    instruction.accounts.push(AccountMeta::new_readonly(sysvar::slot_hashes::ID, false));
+    instruction
+}
+
+/// Przygotowuje instrukcję zapisującą pokój w starszym układzie do powiększenia jego konta:
+/// podpisujący (pierwsze konto) staje się zapisywalny, bo dopłaca czynsz, i dochodzi System Program
+pub fn with_room_resize(mut instruction: Instruction) -> Instruction {
+    instruction.accounts[0].is_writable = true;
+    instruction.accounts.push(AccountMeta::new_readonly(system_program::ID, false));
+    instruction
+}
+
+/// Adres PDA skarbca tokenów pokoju
+pub fn vault_address(program_id: &Pubkey, room: &Pubkey) -> Pubkey {
+    GameRoom::vault_address(program_id, room).0
+}
+
+/// `CreateRoom` z wpisowym w tokenie SPL - `creator_token_account` płaci wpisowe do skarbca pokoju
+#[allow(clippy::too_many_arguments)]
+pub fn create_token_room(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    max_players: u8,
+    entry_fee: u64,
+    room_nonce: u64,
+    mint: &Pubkey,
+    creator_token_account: &Pubkey,
+    options: RoomOptions,
+) -> Instruction {
+    let (room, _) = room_address(program_id, creator, room_nonce);
+    let mut instruction = build_create_room(program_id, creator, max_players, entry_fee, room_nonce, Some(*mint), options);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(vault_address(program_id, &room), false),
+        AccountMeta::new(*creator_token_account, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ]);
+    instruction
+}
+
+/// `JoinRoom` dla pokoju z tokenem SPL
+pub fn join_token_room(program_id: &Pubkey, player: &Pubkey, room: &Pubkey, player_token_account: &Pubkey) -> Instruction {
+    let mut instruction = join_room(program_id, player, room);
+    instruction.accounts.extend([
+        AccountMeta::new(*player_token_account, false),
+        AccountMeta::new(vault_address(program_id, room), false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+    ]);
+    instruction
+}
+
+/// `JoinRoom`
+pub fn join_room(program_id: &Pubkey, player: &Pubkey, room: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::JoinRoom,
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(*room, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// `JoinPrivateRoom` - dołączenie do pokoju chronionego sekretem zaproszenia
+pub fn join_private_room(program_id: &Pubkey, player: &Pubkey, room: &Pubkey, invite_secret: &[u8]) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::JoinPrivateRoom { invite_secret: invite_secret.to_vec() },
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(*room, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// `SetReady`
+pub fn set_ready(program_id: &Pubkey, player: &Pubkey, room: &Pubkey, ready: bool) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::SetReady { ready },
+        vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*room, false),
+        ],
+    )
+}
+
+/// `SetReadyQuorum` (0 = wymagana gotowość wszystkich graczy)
+pub fn set_ready_quorum(program_id: &Pubkey, host: &Pubkey, room: &Pubkey, quorum: u8) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::SetReadyQuorum { quorum },
+        vec![
+            AccountMeta::new_readonly(*host, true),
+            AccountMeta::new(*room, false),
+        ],
+    )
+}
+
+/// `StartGame` - uzupełnia SlotHashes sysvar
+pub fn start_game(program_id: &Pubkey, initiator: &Pubkey, room: &Pubkey, game_id: &str) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::StartGame { game_id: game_id.to_string() },
+        vec![
+            AccountMeta::new_readonly(*initiator, true),
+            AccountMeta::new(*room, false),
+            AccountMeta::new_readonly(sysvar::slot_hashes::ID, false),
+        ],
+    )
+}
+
+/// `CommitSeed` - zobowiązanie wyliczane z ziarna trzymanego przez klienta do czasu ujawnienia
+pub fn commit_seed(program_id: &Pubkey, player: &Pubkey, room: &Pubkey, seed: &[u8; 32]) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::CommitSeed { commitment: seed_commitment(player, seed) },
+        vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*room, false),
+        ],
+    )
+}
+
+/// `RevealSeed`
+pub fn reveal_seed(program_id: &Pubkey, player: &Pubkey, room: &Pubkey, seed: &[u8; 32]) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::RevealSeed { seed: *seed },
+        vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*room, false),
+        ],
+    )
+}
+
+/// `EndGame` podpisany przez serwer gry (`result_authority` pokoju)
+pub fn end_game(program_id: &Pubkey, result_authority: &Pubkey, room: &Pubkey, winner: &Pubkey) -> Instruction {
+    end_game_unanimous(program_id, result_authority, room, winner, &[])
+}
+
+/// `EndGame` zatwierdzony jednogłośnie - `other_players` muszą podpisać transakcję
+pub fn end_game_unanimous(
+    program_id: &Pubkey,
+    initiator: &Pubkey,
+    room: &Pubkey,
+    winner: &Pubkey,
+    other_players: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initiator, true),
+        AccountMeta::new(*room, false),
+    ];
+    accounts.extend(other_players.iter().map(|player| AccountMeta::new_readonly(*player, true)));
+    build(program_id, UnoInstruction::EndGame { winner: *winner }, accounts)
+}
+
+/// `EndGameRanked` - `placements` od zwycięzcy; bez podpisu serwera `other_players` muszą podpisać
+pub fn end_game_ranked(
+    program_id: &Pubkey,
+    initiator: &Pubkey,
+    room: &Pubkey,
+    placements: &[Pubkey],
+    other_players: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initiator, true),
+        AccountMeta::new(*room, false),
+    ];
+    accounts.extend(other_players.iter().map(|player| AccountMeta::new_readonly(*player, true)));
+    build(program_id, UnoInstruction::EndGameRanked { placements: placements.to_vec() }, accounts)
+}
+
+/// `SettleWithTranscript` - bez podpisu serwera gry `other_players` muszą podpisać transakcję
+pub fn settle_with_transcript(
+    program_id: &Pubkey,
+    initiator: &Pubkey,
+    room: &Pubkey,
+    moves: &[Move],
+    other_players: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initiator, true),
+        AccountMeta::new(*room, false),
+    ];
+    accounts.extend(other_players.iter().map(|player| AccountMeta::new_readonly(*player, true)));
+    build(program_id, UnoInstruction::SettleWithTranscript { moves: encode_moves(moves) }, accounts)
+}
+
+/// `ClaimPrize` - `fee_recipient` musi odpowiadać portfelowi z konfiguracji programu
+pub fn claim_prize(
+    program_id: &Pubkey,
+    winner: &Pubkey,
+    room: &Pubkey,
+    creator: &Pubkey,
+    fee_recipient: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::ClaimPrize,
+        vec![
+            AccountMeta::new(*winner, true),
+            AccountMeta::new(*room, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(*fee_recipient, false),
+            AccountMeta::new_readonly(config_address(program_id).0, false),
+            AccountMeta::new(*creator, false),
+        ],
+    )
+}
+
+/// `ClaimPrize` dla pokoju z tokenem SPL - prowizja trafia na ATA portfela platformy,
+/// a reszta tokenów ze skarbca przy jego zamknięciu na ATA twórcy
+pub fn claim_token_prize(
+    program_id: &Pubkey,
+    winner: &Pubkey,
+    room: &Pubkey,
+    creator: &Pubkey,
+    fee_recipient: &Pubkey,
+    mint: &Pubkey,
+    winner_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = claim_prize(program_id, winner, room, creator, fee_recipient);
+    instruction.accounts.extend([
+        AccountMeta::new(*winner_token_account, false),
+        AccountMeta::new(vault_address(program_id, room), false),
+        AccountMeta::new(spl_associated_token_account::get_associated_token_address(fee_recipient, mint), false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(spl_associated_token_account::get_associated_token_address(creator, mint), false),
+    ]);
+    instruction
+}
+
+/// `CancelRoom` dla pokoju z tokenem SPL - `player_token_accounts` w kolejności z pokoju (łącznie z twórcą),
+/// czynsz trafia do twórcy `creator`
+pub fn cancel_token_room(
+    program_id: &Pubkey,
+    host: &Pubkey,
+    creator: &Pubkey,
+    room: &Pubkey,
+    player_token_accounts: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*host, true),
+        AccountMeta::new(*room, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(vault_address(program_id, room), false),
+        AccountMeta::new(*creator, false),
+    ];
+    accounts.extend(player_token_accounts.iter().map(|account| AccountMeta::new(*account, false)));
+    build(program_id, UnoInstruction::CancelRoom, accounts)
+}
+
+/// `CancelRoom` - `players` to lista graczy z pokoju (gospodarz jest pomijany automatycznie)
+pub fn cancel_room(program_id: &Pubkey, host: &Pubkey, room: &Pubkey, players: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*host, true),
+        AccountMeta::new(*room, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    accounts.extend(
+        players
+            .iter()
+            .filter(|player| *player != host)
+            .map(|player| AccountMeta::new(*player, false)),
+    );
+    build(program_id, UnoInstruction::CancelRoom, accounts)
+}
+
+/// `InitializeConfig` - podpisujący (upgrade authority programu) zostaje administratorem konfiguracji
+pub fn initialize_config(program_id: &Pubkey, admin: &Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::InitializeConfig { params },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(program_data_address(program_id), false),
+        ],
+    )
+}
+
+/// `UpdateConfig`
+pub fn update_config(program_id: &Pubkey, admin: &Pubkey, new_admin: &Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::UpdateConfig { new_admin: *new_admin, params },
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(config_address(program_id).0, false),
+        ],
+    )
+}
+
+/// `ReclaimAbandoned` - `players` w kolejności z pokoju (łącznie z twórcą)
+pub fn reclaim_abandoned(program_id: &Pubkey, initiator: &Pubkey, room: &Pubkey, players: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initiator, true),
+        AccountMeta::new(*room, false),
+        AccountMeta::new_readonly(config_address(program_id).0, false),
+    ];
+    accounts.extend(players.iter().map(|player| AccountMeta::new(*player, false)));
+    build(program_id, UnoInstruction::ReclaimAbandoned, accounts)
+}
+
+/// `ReclaimAbandoned` dla pokoju z tokenem SPL - `player_token_accounts` w kolejności z pokoju
+pub fn reclaim_abandoned_tokens(
+    program_id: &Pubkey,
+    initiator: &Pubkey,
+    room: &Pubkey,
+    creator: &Pubkey,
+    player_token_accounts: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initiator, true),
+        AccountMeta::new(*room, false),
+        AccountMeta::new_readonly(config_address(program_id).0, false),
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(vault_address(program_id, room), false),
+        AccountMeta::new(*creator, false),
+    ];
+    accounts.extend(player_token_accounts.iter().map(|account| AccountMeta::new(*account, false)));
+    build(program_id, UnoInstruction::ReclaimAbandoned, accounts)
+}
+
+/// `LeaveRoom` dla pokoju z tokenem SPL
+pub fn leave_token_room(program_id: &Pubkey, player: &Pubkey, room: &Pubkey, player_token_account: &Pubkey) -> Instruction {
+    let mut instruction = leave_room(program_id, player, room);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(vault_address(program_id, room), false),
+        AccountMeta::new(*player_token_account, false),
+    ]);
+    instruction
+}
+
+/// `KickPlayer` - `player` odbiera zwrot wpisowego na swój portfel
+pub fn kick_player(program_id: &Pubkey, host: &Pubkey, room: &Pubkey, player: &Pubkey, ban: bool) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::KickPlayer { player: *player, ban },
+        vec![
+            AccountMeta::new_readonly(*host, true),
+            AccountMeta::new(*room, false),
+            AccountMeta::new(*player, false),
+        ],
+    )
+}
+
+/// `KickPlayer` dla pokoju z tokenem SPL - zwrot trafia na `player_token_account`
+pub fn kick_token_player(
+    program_id: &Pubkey,
+    host: &Pubkey,
+    room: &Pubkey,
+    player: &Pubkey,
+    ban: bool,
+    player_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = kick_player(program_id, host, room, player, ban);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(spl_token::ID, false),
+        AccountMeta::new(vault_address(program_id, room), false),
+        AccountMeta::new(*player_token_account, false),
+    ]);
+    instruction
+}
+
+/// `LeaveRoom`
+pub fn leave_room(program_id: &Pubkey, player: &Pubkey, room: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::LeaveRoom,
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(*room, false),
+        ],
+    )
+}
+
+/// `TransferHost` - podpisuje gospodarz lub gracz przejmujący rolę po czasie nieaktywności gospodarza
+pub fn transfer_host(program_id: &Pubkey, signer: &Pubkey, room: &Pubkey, new_host: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::TransferHost { new_host: *new_host },
+        vec![
+            AccountMeta::new_readonly(*signer, true),
+            AccountMeta::new(*room, false),
+            AccountMeta::new_readonly(config_address(program_id).0, false),
+        ],
+    )
+}
+
+/// `Forfeit`
+pub fn forfeit(program_id: &Pubkey, player: &Pubkey, room: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        UnoInstruction::Forfeit,
+        vec![
+            AccountMeta::new_readonly(*player, true),
+            AccountMeta::new(*room, false),
+        ],
+    )
+}
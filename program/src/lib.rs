@@ -1,2 +1,7 @@
+extern crate alloc;
+
+pub mod error;
+pub mod instruction;
+pub mod rules;
 pub mod uno_program;
-pub use crate::uno_program::*;
\ No newline at end of file
+pub use crate::uno_program::*;
@@ -0,0 +1,428 @@
+// Zasady gry UNO - wspólne dla programu on-chain i natywnego serwera gry
+//
+// Moduł korzysta wyłącznie z `core` i `alloc`, nie zależy od `solana_program`
+// ani od zegara czy losowości - talię tasuje się deterministycznie z ziarna
+// zapisanego w pokoju, a cały stan gry zmienia się w odpowiedzi na ruchy graczy.
+// Dzięki temu program może odtworzyć rozgrywkę z zapisu ruchów (`replay`).
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Liczba kart w standardowej talii UNO
+pub const DECK_SIZE: usize = 108;
+
+/// Liczba kart rozdawanych każdemu graczowi na początku gry
+pub const INITIAL_HAND_SIZE: usize = 7;
+
+/// Kolor karty - `Black` oznacza nieprzypisany kolor kart Wild
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Blue,
+    Green,
+    Yellow,
+    Black,
+}
+
+impl Color {
+    /// Kolory, które można wskazać po zagraniu karty Wild
+    pub const PLAYABLE: [Color; 4] = [Color::Red, Color::Blue, Color::Green, Color::Yellow];
+
+    pub fn is_playable(self) -> bool {
+        self != Color::Black
+    }
+}
+
+/// Wartość karty
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Value {
+    Number(u8),
+    Skip,
+    Reverse,
+    Draw2,
+    Wild,
+    Wild4,
+}
+
+impl Value {
+    /// Liczba kart dobieranych przez następnego gracza po zagraniu tej karty
+    pub fn draw_penalty(self) -> usize {
+        match self {
+            Value::Draw2 => 2,
+            Value::Wild4 => 4,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub color: Color,
+    pub value: Value,
+}
+
+impl Card {
+    pub const fn new(color: Color, value: Value) -> Self {
+        Self { color, value }
+    }
+
+    pub fn is_wild(&self) -> bool {
+        matches!(self.value, Value::Wild | Value::Wild4)
+    }
+
+    /// Czy kartę można położyć na `top` (kolor `top` po Wild to kolor wybrany przez gracza)
+    pub fn can_play_on(&self, top: &Card) -> bool {
+        // Karta Wild lub Wild4 zawsze może być zagrana. Na Wild odkryty na początku gry
+        // (bez wybranego koloru) pasuje tylko inna dzika karta, jak w `isValidMove` serwera gry
+        if self.is_wild() {
+            return true;
+        }
+
+        // Zgodność koloru lub wartości
+        self.color == top.color || self.value == top.value
+    }
+}
+
+/// Pełna, nieprzetasowana talia 108 kart w kolejności jak na serwerze gry
+pub fn standard_deck() -> Vec<Card> {
+    let mut deck = Vec::with_capacity(DECK_SIZE);
+
+    // Karty kolorowe: jedno 0 i po dwie karty pozostałych wartości
+    for color in Color::PLAYABLE {
+        for value in (0..=9).map(Value::Number).chain([Value::Skip, Value::Reverse, Value::Draw2]) {
+            deck.push(Card::new(color, value));
+            if value != Value::Number(0) {
+                deck.push(Card::new(color, value));
+            }
+        }
+    }
+
+    // Karty specjalne
+    for _ in 0..4 {
+        deck.push(Card::new(Color::Black, Value::Wild));
+        deck.push(Card::new(Color::Black, Value::Wild4));
+    }
+
+    deck
+}
+
+/// Tasuje talię algorytmem Fishera-Yatesa (jak serwer gry), losując indeksy
+/// generatorem SplitMix64 zainicjowanym ziarnem talii pokoju
+pub fn shuffle(deck: &mut [Card], seed: &[u8; 32]) {
+    // Każde 8 bajtów ziarna przechodzi przez mieszanie, więc liczy się całe ziarno
+    let mut state = 0u64;
+    for chunk in seed.chunks_exact(8) {
+        state ^= u64::from_le_bytes(chunk.try_into().unwrap());
+        state = splitmix64(&mut state);
+    }
+
+    for i in (1..deck.len()).rev() {
+        // Minimalne obciążenie modulo jest bez znaczenia dla talii 108 kart
+        let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+        deck.swap(i, j);
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Standardowa talia przetasowana ziarnem pokoju
+pub fn shuffled_deck(seed: &[u8; 32]) -> Vec<Card> {
+    let mut deck = standard_deck();
+    shuffle(&mut deck, seed);
+    deck
+}
+
+/// Kierunek kolejki
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Direction {
+    pub fn reversed(self) -> Self {
+        match self {
+            Direction::Clockwise => Direction::CounterClockwise,
+            Direction::CounterClockwise => Direction::Clockwise,
+        }
+    }
+}
+
+/// Błędy ruchów odrzucanych przez zasady gry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleError {
+    /// Liczba graczy poza zakresem 2..=10
+    InvalidPlayerCount,
+    /// Talia ma za mało kart, żeby rozdać ręce i odkryć pierwszą kartę
+    NotEnoughCards,
+    /// Gra ma już zwycięzcę
+    GameOver,
+    /// Nie ma gracza o takim indeksie
+    InvalidPlayer,
+    /// Ruch gracza poza jego kolejką
+    NotYourTurn,
+    /// Nie ma karty o takim indeksie w ręce gracza
+    InvalidCardIndex,
+    /// Karty nie można położyć na bieżącą kartę
+    IllegalMove,
+    /// Po zagraniu Wild trzeba wybrać jeden z czterech kolorów
+    InvalidColorChoice,
+    /// W talii nie ma już kart do dobrania
+    DeckEmpty,
+    /// Zapis ruchu nie odpowiada żadnemu ruchowi
+    InvalidMoveEncoding,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            RuleError::InvalidPlayerCount => "Invalid player count",
+            RuleError::NotEnoughCards => "Not enough cards to deal",
+            RuleError::GameOver => "Game is over",
+            RuleError::InvalidPlayer => "Invalid player index",
+            RuleError::NotYourTurn => "Not this player's turn",
+            RuleError::InvalidCardIndex => "Invalid card index",
+            RuleError::IllegalMove => "Card cannot be played on the current card",
+            RuleError::InvalidColorChoice => "Wild card requires a playable color",
+            RuleError::DeckEmpty => "Deck is empty",
+            RuleError::InvalidMoveEncoding => "Invalid move encoding",
+        };
+        f.write_str(message)
+    }
+}
+
+/// Wynik poprawnie wykonanego zagrania
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayOutcome {
+    /// Gra toczy się dalej, kolej przechodzi na wskazanego gracza
+    Continue { next_player: usize },
+    /// Gracz pozbył się ostatniej karty
+    Won { winner: usize },
+}
+
+/// Ruch gracza, którego kolej akurat trwa
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    /// Zagranie karty z ręki; `chosen_color` tylko dla kart Wild
+    Play { card_index: u8, chosen_color: Option<Color> },
+    /// Dobranie karty z talii
+    Draw,
+}
+
+impl Move {
+    /// Rozmiar zakodowanego ruchu w zapisie rozgrywki
+    pub const ENCODED_SIZE: usize = 2;
+
+    /// Koduje ruch jako `[znacznik, indeks karty]`: 0 - dobranie, 1 - zagranie,
+    /// 2..=5 - zagranie Wild z wyborem koloru (czerwony, niebieski, zielony, żółty)
+    pub fn encode(&self) -> [u8; Self::ENCODED_SIZE] {
+        match *self {
+            Move::Draw => [0, 0],
+            Move::Play { card_index, chosen_color: None } => [1, card_index],
+            Move::Play { card_index, chosen_color: Some(color) } => {
+                let position = Color::PLAYABLE.iter().position(|c| *c == color).unwrap_or(Color::PLAYABLE.len());
+                [2 + position as u8, card_index]
+            }
+        }
+    }
+
+    pub fn decode(bytes: [u8; Self::ENCODED_SIZE]) -> Result<Self, RuleError> {
+        match bytes {
+            [0, 0] => Ok(Move::Draw),
+            [1, card_index] => Ok(Move::Play { card_index, chosen_color: None }),
+            [tag @ 2..=5, card_index] => Ok(Move::Play {
+                card_index,
+                chosen_color: Some(Color::PLAYABLE[(tag - 2) as usize]),
+            }),
+            _ => Err(RuleError::InvalidMoveEncoding),
+        }
+    }
+}
+
+/// Koduje listę ruchów do zapisu rozgrywki przesyłanego do programu
+pub fn encode_moves(moves: &[Move]) -> Vec<u8> {
+    moves.iter().flat_map(Move::encode).collect()
+}
+
+/// Dekoduje zapis rozgrywki z powrotem na listę ruchów
+pub fn decode_moves(bytes: &[u8]) -> Result<Vec<Move>, RuleError> {
+    let chunks = bytes.chunks_exact(Move::ENCODED_SIZE);
+    if !chunks.remainder().is_empty() {
+        return Err(RuleError::InvalidMoveEncoding);
+    }
+    chunks
+        .map(|chunk| Move::decode([chunk[0], chunk[1]]))
+        .collect()
+}
+
+/// Odtwarza rozgrywkę od rozdania z talii przetasowanej ziarnem `deck_seed`,
+/// stosując kolejno ruchy graczy; zwraca stan po ostatnim ruchu
+pub fn replay(player_count: usize, deck_seed: &[u8; 32], moves: &[Move]) -> Result<GameState, RuleError> {
+    let mut state = GameState::new(player_count, shuffled_deck(deck_seed))?;
+    for game_move in moves {
+        state.apply(*game_move)?;
+    }
+    Ok(state)
+}
+
+/// Stan rozgrywki; gracze są identyfikowani indeksem w kolejności z pokoju
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameState {
+    pub hands: Vec<Vec<Card>>,
+    /// Talia do dobierania - karty są zdejmowane z końca
+    pub draw_pile: Vec<Card>,
+    /// Karta na wierzchu stosu (po Wild z kolorem wybranym przez gracza)
+    pub top_card: Card,
+    pub current_player: usize,
+    pub direction: Direction,
+    pub winner: Option<usize>,
+}
+
+impl GameState {
+    pub const MIN_PLAYERS: usize = 2;
+    pub const MAX_PLAYERS: usize = 10;
+
+    /// Rozdaje po 7 kart z przetasowanej talii i odkrywa pierwszą kartę
+    pub fn new(player_count: usize, mut deck: Vec<Card>) -> Result<Self, RuleError> {
+        if !(Self::MIN_PLAYERS..=Self::MAX_PLAYERS).contains(&player_count) {
+            return Err(RuleError::InvalidPlayerCount);
+        }
+        if deck.len() < player_count * INITIAL_HAND_SIZE + 1 {
+            return Err(RuleError::NotEnoughCards);
+        }
+
+        let mut hands = Vec::with_capacity(player_count);
+        for _ in 0..player_count {
+            let split_at = deck.len() - INITIAL_HAND_SIZE;
+            let mut hand = deck.split_off(split_at);
+            // Kolejność jak przy dobieraniu pojedynczych kart z wierzchu talii
+            hand.reverse();
+            hands.push(hand);
+        }
+        let top_card = deck.pop().ok_or(RuleError::NotEnoughCards)?;
+
+        Ok(Self {
+            hands,
+            draw_pile: deck,
+            top_card,
+            current_player: 0,
+            direction: Direction::Clockwise,
+            winner: None,
+        })
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.hands.len()
+    }
+
+    /// Indeks gracza następnego po `from` w bieżącym kierunku
+    pub fn next_player_index(&self, from: usize) -> usize {
+        let count = self.player_count();
+        match self.direction {
+            Direction::Clockwise => (from + 1) % count,
+            Direction::CounterClockwise => (from + count - 1) % count,
+        }
+    }
+
+    /// Zagrywa kartę `card_index` z ręki gracza; `chosen_color` jest wymagany dla Wild
+    pub fn play_card(
+        &mut self,
+        player: usize,
+        card_index: usize,
+        chosen_color: Option<Color>,
+    ) -> Result<PlayOutcome, RuleError> {
+        self.check_turn(player)?;
+
+        let card = *self.hands[player].get(card_index).ok_or(RuleError::InvalidCardIndex)?;
+        if !card.can_play_on(&self.top_card) {
+            return Err(RuleError::IllegalMove);
+        }
+
+        // Jeśli to karta Wild, ustaw wybrany kolor
+        self.top_card = if card.is_wild() {
+            match chosen_color {
+                Some(color) if color.is_playable() => Card::new(color, card.value),
+                _ => return Err(RuleError::InvalidColorChoice),
+            }
+        } else {
+            card
+        };
+        self.hands[player].remove(card_index);
+
+        // Sprawdź, czy gracz wygrał
+        if self.hands[player].is_empty() {
+            self.winner = Some(player);
+            return Ok(PlayOutcome::Won { winner: player });
+        }
+
+        self.apply_card_effect(player, card.value);
+        Ok(PlayOutcome::Continue { next_player: self.current_player })
+    }
+
+    /// Wykonuje ruch w imieniu gracza, którego jest kolej
+    pub fn apply(&mut self, game_move: Move) -> Result<(), RuleError> {
+        let player = self.current_player;
+        match game_move {
+            Move::Play { card_index, chosen_color } => {
+                self.play_card(player, card_index as usize, chosen_color).map(|_| ())
+            }
+            Move::Draw => self.draw_card(player).map(|_| ()),
+        }
+    }
+
+    /// Dobiera jedną kartę z talii i oddaje kolejkę następnemu graczowi
+    pub fn draw_card(&mut self, player: usize) -> Result<Card, RuleError> {
+        self.check_turn(player)?;
+
+        let card = self.draw_pile.pop().ok_or(RuleError::DeckEmpty)?;
+        self.hands[player].push(card);
+        self.current_player = self.next_player_index(player);
+        Ok(card)
+    }
+
+    fn check_turn(&self, player: usize) -> Result<(), RuleError> {
+        if self.winner.is_some() {
+            return Err(RuleError::GameOver);
+        }
+        if player >= self.player_count() {
+            return Err(RuleError::InvalidPlayer);
+        }
+        if player != self.current_player {
+            return Err(RuleError::NotYourTurn);
+        }
+        Ok(())
+    }
+
+    // Efekty kart specjalnych i przekazanie kolejki
+    fn apply_card_effect(&mut self, player: usize, value: Value) {
+        let next = self.next_player_index(player);
+
+        self.current_player = match value {
+            // Pomiń następnego gracza
+            Value::Skip => self.next_player_index(next),
+            Value::Reverse => {
+                // Po zmianie kierunku kolej przechodzi dalej - także przy dwóch graczach,
+                // tak jak na serwerze gry (`server/game/UnoGame.js`)
+                self.direction = self.direction.reversed();
+                self.next_player_index(player)
+            }
+            // Następny gracz dobiera karty (tyle, ile zostało w talii) i traci kolejkę
+            Value::Draw2 | Value::Wild4 => {
+                for _ in 0..value.draw_penalty() {
+                    match self.draw_pile.pop() {
+                        Some(card) => self.hands[next].push(card),
+                        None => break,
+                    }
+                }
+                self.next_player_index(next)
+            }
+            Value::Number(_) | Value::Wild => next,
+        };
+    }
+}
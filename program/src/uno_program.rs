@@ -2,17 +2,105 @@
 
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     entrypoint,
     entrypoint::ProgramResult,
+    log::sol_log_data,
     msg,
     program_error::ProgramError,
     pubkey::Pubkey,
     program::{invoke, invoke_signed},
+    program_utils::limited_deserialize,
     system_instruction,
     sysvar::{rent::Rent, Sysvar, clock::Clock},
+    hash::hashv,
 };
 use borsh::{BorshDeserialize, BorshSerialize};
-use std::str::FromStr;
+use spl_token::state::Account as TokenAccount;
+use solana_program::program_pack::Pack;
+use crate::error::UnoError;
+use crate::rules;
+
+/// Seed PDA globalnej konfiguracji programu
+pub const CONFIG_SEED: &[u8] = b"uno_config";
+
+/// Seed PDA pokoju gry: `[b"uno_game", twórca, nonce (u64 LE)]` (starsze pokoje: `[b"uno_game", twórca, slot]`)
+pub const ROOM_SEED: &[u8] = b"uno_game";
+
+/// Seed PDA licznika pokojów twórcy: `[b"uno_creator", twórca]`
+pub const CREATOR_SEED: &[u8] = b"uno_creator";
+
+/// Seed PDA skarbca tokenów pokoju: `[b"uno_vault", pokój]`
+pub const VAULT_SEED: &[u8] = b"uno_vault";
+
+/// Maksymalna liczba graczy w pokoju (oficjalne zasady UNO dopuszczają 2-10)
+pub const MAX_PLAYERS_LIMIT: u8 = 10;
+
+/// Maksymalna długość listy zaproszonych graczy (ogranicza rozmiar transakcji i konta pokoju)
+pub const MAX_ALLOWLIST_LEN: usize = 20;
+
+/// Maksymalna liczba graczy zbanowanych w jednym pokoju
+pub const MAX_BANNED_PLAYERS: usize = 8;
+
+/// Czas nieaktywności gospodarza nadawany konfiguracjom zapisanym przed `host_inactivity_secs`
+pub const DEFAULT_HOST_INACTIVITY_SECS: i64 = 15 * 60;
+
+/// Mianownik dla prowizji wyrażonej w punktach bazowych (10 000 = 100%)
+pub const BASIS_POINTS_DENOMINATOR: u64 = 10_000;
+
+/// Domyślna tabela wypłat - cała pula dla zwycięzcy
+pub const WINNER_TAKES_ALL_BPS: u16 = 10_000;
+
+/// Rozmiar wspólnego nagłówka kont programu:
+/// dyskryminator typu (8 bajtów) + wersja układu (1 bajt) + rozmiar danych Borsh (4 bajty)
+pub const ACCOUNT_HEADER_SIZE: usize = 8 + 1 + 4;
+
+/// Odczytuje nagłówek konta i zwraca wersję układu oraz dane Borsh
+pub fn unpack_account_data<'a>(data: &'a [u8], discriminator: &[u8; 8]) -> Result<(u8, &'a [u8]), ProgramError> {
+    // Sprawdzamy czy dane mają minimalny rozmiar
+    if data.len() < ACCOUNT_HEADER_SIZE {
+        msg!("Account data too small for header");
+        return Err(UnoError::InvalidAccountType.into());
+    }
+    
+    // Sprawdzamy typ konta
+    if data[..8] != discriminator[..] {
+        msg!("Account discriminator mismatch. Expected: {:?}, got: {:?}", discriminator, &data[..8]);
+        return Err(UnoError::InvalidAccountType.into());
+    }
+    
+    let version = data[8];
+    
+    // Odczytaj rozmiar danych z nagłówka
+    let size_bytes: [u8; 4] = data[9..ACCOUNT_HEADER_SIZE].try_into()
+        .map_err(|_| UnoError::InvalidAccountType)?;
+    let data_size = u32::from_le_bytes(size_bytes) as usize;
+    
+    // Sprawdź czy mamy wystarczająco danych
+    if data.len() < ACCOUNT_HEADER_SIZE + data_size {
+        msg!("Not enough data. Expected: {}, got: {}", ACCOUNT_HEADER_SIZE + data_size, data.len());
+        return Err(UnoError::InvalidAccountType.into());
+    }
+    
+    Ok((version, &data[ACCOUNT_HEADER_SIZE..ACCOUNT_HEADER_SIZE + data_size]))
+}
+
+/// Zapisuje nagłówek konta (dyskryminator, wersję, rozmiar) oraz dane Borsh, zerując resztę bufora
+pub fn pack_account_data(data: &mut [u8], discriminator: &[u8; 8], version: u8, payload: &[u8]) -> ProgramResult {
+    // Sprawdź czy zmieści się w buforze
+    if ACCOUNT_HEADER_SIZE + payload.len() > data.len() {
+        msg!("Account too small. Needs: {}, has: {}", ACCOUNT_HEADER_SIZE + payload.len(), data.len());
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    
+    data.fill(0);
+    data[..8].copy_from_slice(discriminator);
+    data[8] = version;
+    data[9..ACCOUNT_HEADER_SIZE].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+    data[ACCOUNT_HEADER_SIZE..ACCOUNT_HEADER_SIZE + payload.len()].copy_from_slice(payload);
+    
+    Ok(())
+}
 
 /// Definicja stanów gry
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
@@ -22,13 +110,66 @@ pub enum GameStatus {
     Completed,
 }
 
+/// Kto może dołączyć do pokoju
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum RoomAccess {
+    /// Każdy, kto zna adres pokoju
+    Public,
+    /// Tylko wymienieni gracze
+    Allowlist(Vec<Pubkey>),
+    /// Gracze znający sekret zaproszenia o podanym hashu (`invite_hash`) - sekret
+    /// staje się jawny w pierwszej transakcji `JoinPrivateRoom`, więc dla stałych
+    /// składów lepsza jest lista graczy
+    InviteHash([u8; 32]),
+}
+
+impl RoomAccess {
+    // Lista zaproszonych nie może być pusta, zbyt długa ani zawierać powtórzeń
+    pub fn validate(&self) -> ProgramResult {
+        if let RoomAccess::Allowlist(allowed) = self {
+            let has_duplicates = allowed.iter().enumerate().any(|(i, key)| allowed[..i].contains(key));
+            if allowed.is_empty() || allowed.len() > MAX_ALLOWLIST_LEN || has_duplicates {
+                msg!("Error: Invalid allowlist of {} players", allowed.len());
+                return Err(UnoError::InvalidRoomAccess.into());
+            }
+        }
+        Ok(())
+    }
+    
+    // Sprawdza, czy gracz może dołączyć - dla pokoju z hasłem wymagany jest sekret zaproszenia
+    pub fn admit(&self, player: &Pubkey, invite_secret: Option<&[u8]>) -> ProgramResult {
+        match self {
+            RoomAccess::Public => Ok(()),
+            RoomAccess::Allowlist(allowed) => {
+                if !allowed.contains(player) {
+                    msg!("Error: Player {} is not on the room allowlist", player);
+                    return Err(UnoError::NotInvited.into());
+                }
+                Ok(())
+            }
+            RoomAccess::InviteHash(expected) => {
+                if invite_secret.map(invite_hash).as_ref() != Some(expected) {
+                    msg!("Error: Missing or invalid invite secret");
+                    return Err(UnoError::InvalidInviteSecret.into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Hash sekretu zaproszenia zapisywany w pokoju prywatnym (`RoomAccess::InviteHash`)
+pub fn invite_hash(invite_secret: &[u8]) -> [u8; 32] {
+    hashv(&[invite_secret]).to_bytes()
+}
+
 /// Struktura danych pokoju (gry) - zoptymalizowana dla modelu hybrydowego
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct GameRoom {
     pub creator: Pubkey,                  // 32 bajty
     pub max_players: u8,                  // 1 bajt
-    pub entry_fee_lamports: u64,          // 8 bajtów
-    pub players: Vec<Pubkey>,             // 4 + (32 * max 4) = 132 bajty
+    pub entry_fee_lamports: u64,          // 8 bajtów - w jednostkach tokena, gdy ustawiony `mint`
+    pub players: Vec<Pubkey>,             // 4 + 32 * max_players bajtów
     pub status: GameStatus,               // 1 bajt (enum)
     pub winner: Option<Pubkey>,           // 1 + 32 = 33 bajty
     pub created_at: i64,                  // 8 bajtów
@@ -36,14 +177,215 @@ pub struct GameRoom {
     pub game_ended_at: Option<i64>,       // 1 + 8 = 9 bajtów
     pub prize_claimed: bool,              // 1 bajt
     pub game_id: [u8; 64],               // 64 bajty - stały rozmiar zamiast String
-    pub room_slot: u8,                   // 1 bajt - numer slotu pokoju
+    pub room_slot: u8,                   // 1 bajt - numer slotu pokoju sprzed licznika twórcy (stare pokoje)
+    pub result_authority: Pubkey,         // 32 bajty - serwer uprawniony do ogłoszenia zwycięzcy
+    pub bump: u8,                         // 1 bajt - kanoniczny bump PDA pokoju
+    pub deck_seed: [u8; 32],              // 32 bajty - ziarno tasowania talii ustalone przy starcie gry
+    pub seed_commitments: Vec<[u8; 32]>,  // 4 + 32 * max_players - zobowiązania graczy (zera = brak)
+    pub seeds_revealed: Vec<bool>,        // 4 + max_players - czy gracz ujawnił swoje ziarno
+    pub seed_accumulator: [u8; 32],       // 32 bajty - XOR ujawnionych ziaren graczy
+    pub mint: Option<Pubkey>,             // 1 + 32 = 33 bajty - token SPL wpisowego (brak = SOL)
+    pub vault_bump: u8,                   // 1 bajt - bump PDA skarbca tokenów
+    pub payout_bps: Vec<u16>,             // 4 + 2 * max_players - udziały kolejnych miejsc w puli
+    pub placements: Vec<Pubkey>,          // 4 + 32 * max_players - kolejność zakończenia gry
+    pub payouts_claimed: Vec<bool>,       // 4 + max_players - odebrane udziały (równoległe do `payout_bps`)
+    pub room_nonce: Option<u64>,          // 1 + 8 = 9 bajtów - nonce z licznika twórcy (brak = pokój adresowany slotem)
+    pub access: RoomAccess,               // 1 + do 4 + 32 * MAX_ALLOWLIST_LEN bajtów - kto może dołączyć
+    pub ready: Vec<bool>,                 // 4 + max_players - gotowość graczy (równoległe do `players`)
+    pub ready_quorum: u8,                 // 1 bajt - liczba gotowych graczy wymagana do startu (0 = wszyscy)
+    pub auto_start: bool,                 // 1 bajt - gra startuje sama, gdy pokój się zapełni
+    pub banned: Vec<Pubkey>,              // 4 + 32 * MAX_BANNED_PLAYERS bajtów - gracze wyrzuceni z zakazem powrotu
+    pub host: Pubkey,                     // 32 bajty - gospodarz zarządzający pokojem (na starcie twórca)
+    pub host_active_at: i64,              // 8 bajtów - ostatnia aktywność gospodarza
+    pub forfeited: Vec<Pubkey>,           // 4 + 32 * max_players - gracze, którzy się poddali (w kolejności poddania)
 }
 
-impl GameRoom {
+/// Układ pokoju sprzed nagłówka kont (pierwsza wersja programu): 4 bajty rozmiaru danych,
+/// po nich dane Borsh, bez dyskryminatora i wersji, na koncie o stałym rozmiarze `SIZE`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LegacyGameRoom {
+    pub creator: Pubkey,
+    pub max_players: u8,
+    pub entry_fee_lamports: u64,
+    pub players: Vec<Pubkey>,
+    pub status: GameStatus,
+    pub winner: Option<Pubkey>,
+    pub created_at: i64,
+    pub game_started_at: Option<i64>,
+    pub game_ended_at: Option<i64>,
+    pub prize_claimed: bool,
+    pub game_id: [u8; 64],
+    pub room_slot: u8,
+}
+
+impl LegacyGameRoom {
+    /// Rozmiar konta pokoju w pierwszej wersji programu
     pub const SIZE: usize = 512;
-    pub const HEADER_SIZE: usize = 4; // Pierwsze 4 bajty przechowują rozmiar danych
+    /// Rozmiar nagłówka z długością danych
+    pub const HEADER_SIZE: usize = 4;
+    
+    // Odczyt danych w układzie sprzed nagłówka kont
+    fn from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        let size_bytes: [u8; 4] = data.get(..Self::HEADER_SIZE)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(UnoError::InvalidAccountType)?;
+        let data_size = u32::from_le_bytes(size_bytes) as usize;
+        
+        let payload = data.get(Self::HEADER_SIZE..Self::HEADER_SIZE + data_size).ok_or_else(|| {
+            msg!("Not enough data for legacy room. Expected: {}, got: {}", Self::HEADER_SIZE + data_size, data.len());
+            UnoError::InvalidAccountType
+        })?;
+        
+        Self::try_from_slice(payload).map_err(|e| {
+            msg!("Legacy room deserialization error: {:?}", e);
+            UnoError::InvalidAccountType.into()
+        })
+    }
     
-    pub fn new(creator: Pubkey, max_players: u8, entry_fee_lamports: u64, created_at: i64, room_slot: u8) -> Self {
+    // Migracja do wersji 1 - pierwsza wersja programu nie miała serwera wyników,
+    // więc wynik takiego pokoju zatwierdzają jednogłośnie gracze
+    fn migrate(self) -> GameRoomV1 {
+        GameRoomV1 {
+            creator: self.creator,
+            max_players: self.max_players,
+            entry_fee_lamports: self.entry_fee_lamports,
+            players: self.players,
+            status: self.status,
+            winner: self.winner,
+            created_at: self.created_at,
+            game_started_at: self.game_started_at,
+            game_ended_at: self.game_ended_at,
+            prize_claimed: self.prize_claimed,
+            game_id: self.game_id,
+            room_slot: self.room_slot,
+            result_authority: Pubkey::default(),
+        }
+    }
+}
+
+/// Zamrożony układ danych pokoju w wersji 1 (nagłówek konta, przed zapisaniem bumpa PDA)
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct GameRoomV1 {
+    pub creator: Pubkey,
+    pub max_players: u8,
+    pub entry_fee_lamports: u64,
+    pub players: Vec<Pubkey>,
+    pub status: GameStatus,
+    pub winner: Option<Pubkey>,
+    pub created_at: i64,
+    pub game_started_at: Option<i64>,
+    pub game_ended_at: Option<i64>,
+    pub prize_claimed: bool,
+    pub game_id: [u8; 64],
+    pub room_slot: u8,
+    pub result_authority: Pubkey,
+}
+
+impl GameRoomV1 {
+    // Migracja do wersji 2 - bump uzupełnia `load_room`
+    fn migrate(self) -> GameRoomV2 {
+        GameRoomV2 {
+            creator: self.creator,
+            max_players: self.max_players,
+            entry_fee_lamports: self.entry_fee_lamports,
+            players: self.players,
+            status: self.status,
+            winner: self.winner,
+            created_at: self.created_at,
+            game_started_at: self.game_started_at,
+            game_ended_at: self.game_ended_at,
+            prize_claimed: self.prize_claimed,
+            game_id: self.game_id,
+            room_slot: self.room_slot,
+            result_authority: self.result_authority,
+            bump: 0,
+        }
+    }
+}
+
+/// Zamrożony układ danych pokoju w wersji 2 (z kanonicznym bumpem PDA, adresowany slotem)
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct GameRoomV2 {
+    pub creator: Pubkey,
+    pub max_players: u8,
+    pub entry_fee_lamports: u64,
+    pub players: Vec<Pubkey>,
+    pub status: GameStatus,
+    pub winner: Option<Pubkey>,
+    pub created_at: i64,
+    pub game_started_at: Option<i64>,
+    pub game_ended_at: Option<i64>,
+    pub prize_claimed: bool,
+    pub game_id: [u8; 64],
+    pub room_slot: u8,
+    pub result_authority: Pubkey,
+    pub bump: u8,
+}
+
+impl GameRoomV2 {
+    // Migracja do bieżącego układu - pokój w SOL, wypłata dla zwycięzcy, bez gotowości i ziaren
+    fn migrate(self) -> GameRoom {
+        let player_count = self.players.len();
+        GameRoom {
+            creator: self.creator,
+            max_players: self.max_players,
+            entry_fee_lamports: self.entry_fee_lamports,
+            players: self.players,
+            status: self.status,
+            winner: self.winner,
+            created_at: self.created_at,
+            game_started_at: self.game_started_at,
+            game_ended_at: self.game_ended_at,
+            prize_claimed: self.prize_claimed,
+            game_id: self.game_id,
+            room_slot: self.room_slot,
+            result_authority: self.result_authority,
+            bump: self.bump,
+            deck_seed: [0u8; 32],
+            seed_commitments: vec![[0u8; 32]; player_count],
+            seeds_revealed: vec![false; player_count],
+            seed_accumulator: [0u8; 32],
+            mint: None,
+            vault_bump: 0,
+            payout_bps: vec![WINNER_TAKES_ALL_BPS],
+            placements: self.winner.into_iter().collect(),
+            payouts_claimed: vec![self.prize_claimed],
+            room_nonce: None,
+            access: RoomAccess::Public,
+            ready: vec![false; player_count],
+            ready_quorum: 0,
+            auto_start: false,
+            banned: Vec::new(),
+            host: self.creator,
+            host_active_at: self.created_at,
+            forfeited: Vec::new(),
+        }
+    }
+}
+
+/// Zobowiązanie gracza do ziarna talii: `hash(ziarno, gracz)` - powiązanie z kluczem
+/// gracza uniemożliwia skopiowanie cudzego zobowiązania
+pub fn seed_commitment(player: &Pubkey, seed: &[u8; 32]) -> [u8; 32] {
+    hashv(&[seed, player.as_ref()]).to_bytes()
+}
+
+impl GameRoom {
+    pub const DISCRIMINATOR: [u8; 8] = *b"uno:room";
+    /// Bieżąca wersja układu danych pokoju - zwiększana przy każdej zmianie pól.
+    /// Starsze wersje (`GameRoomV1`, `GameRoomV2`) są migrowane przy odczycie.
+    pub const VERSION: u8 = 3;
+    /// Wersja przypisywana pokojom w układzie sprzed nagłówka kont (`LegacyGameRoom`)
+    pub const LEGACY_VERSION: u8 = 0;
+    
+    pub fn new(
+        creator: Pubkey,
+        max_players: u8,
+        entry_fee_lamports: u64,
+        created_at: i64,
+        room_nonce: u64,
+        result_authority: Pubkey,
+        bump: u8,
+    ) -> Self {
         Self {
             creator,
             max_players,
@@ -56,73 +398,272 @@ impl GameRoom {
             game_ended_at: None,
             prize_claimed: false,
             game_id: [0u8; 64],
-            room_slot,
+            room_slot: 0,
+            result_authority,
+            bump,
+            deck_seed: [0u8; 32],
+            seed_commitments: vec![[0u8; 32]],
+            seeds_revealed: vec![false],
+            seed_accumulator: [0u8; 32],
+            mint: None,
+            vault_bump: 0,
+            payout_bps: vec![WINNER_TAKES_ALL_BPS],
+            placements: Vec::new(),
+            payouts_claimed: vec![false],
+            room_nonce: Some(room_nonce),
+            access: RoomAccess::Public,
+            ready: vec![false],
+            ready_quorum: 0,
+            auto_start: false,
+            banned: Vec::new(),
+            host: creator,
+            host_active_at: created_at,
+            forfeited: Vec::new(),
+        }
+    }
+    
+    /// Seed adresu pokoju - nonce z licznika twórcy, a dla starszych pokojów numer slotu
+    pub fn address_seed(&self) -> Vec<u8> {
+        match self.room_nonce {
+            Some(room_nonce) => room_nonce.to_le_bytes().to_vec(),
+            None => vec![self.room_slot],
         }
     }
     
+    /// Rozmiar konta publicznego pokoju dla `max_players` - liczony z serializacji pokoju
+    /// w najbardziej pojemnym stanie, więc nowe pola nie wymagają ręcznej aktualizacji
+    pub fn space(max_players: u8) -> usize {
+        Self::space_with_access(max_players, &RoomAccess::Public)
+    }
+    
+    /// Rozmiar konta pokoju z podanymi zasadami dostępu
+    pub fn space_with_access(max_players: u8, access: &RoomAccess) -> usize {
+        let seats = max_players as usize;
+        let mut room = Self::new(Pubkey::default(), max_players, 0, 0, 0, Pubkey::default(), 0);
+        room.access = access.clone();
+        room.players = vec![Pubkey::default(); seats];
+        room.seed_commitments = vec![[0u8; 32]; seats];
+        room.seeds_revealed = vec![false; seats];
+        room.winner = Some(Pubkey::default());
+        room.game_started_at = Some(0);
+        room.game_ended_at = Some(0);
+        room.mint = Some(Pubkey::default());
+        room.payout_bps = vec![0; seats];
+        room.placements = vec![Pubkey::default(); seats];
+        room.payouts_claimed = vec![false; seats];
+        room.ready = vec![false; seats];
+        room.banned = vec![Pubkey::default(); MAX_BANNED_PLAYERS];
+        room.forfeited = vec![Pubkey::default(); seats];
+        
+        let payload_size = room.try_to_vec().map(|payload| payload.len()).unwrap_or_default();
+        ACCOUNT_HEADER_SIZE + payload_size
+    }
+    
     // Metoda do bezpiecznej deserializacji
     pub fn from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
-        // Sprawdzamy czy dane mają minimalny rozmiar
-        if data.len() < Self::HEADER_SIZE {
-            msg!("Account data too small for header");
-            return Err(ProgramError::InvalidAccountData);
+        Self::from_account_data_with_version(data).map(|(game_room, _)| game_room)
+    }
+    
+    // Deserializacja zwracająca także wersję układu zapisaną na koncie
+    pub fn from_account_data_with_version(data: &[u8]) -> Result<(Self, u8), ProgramError> {
+        // Pokoje z pierwszej wersji programu nie mają dyskryminatora - wczytujemy je
+        // w dawnym układzie, żeby wpisowe z nich dało się zwrócić lub wypłacić
+        if !data.starts_with(&Self::DISCRIMINATOR) {
+            msg!("Room without account header, reading legacy layout");
+            let game_room = LegacyGameRoom::from_account_data(data)?.migrate().migrate().migrate();
+            return Ok((game_room, Self::LEGACY_VERSION));
         }
         
-        // Odczytaj rozmiar danych z pierwszych 4 bajtów
-        let size_bytes: [u8; 4] = data[..4].try_into()
-            .map_err(|_| ProgramError::InvalidAccountData)?;
-        let data_size = u32::from_le_bytes(size_bytes) as usize;
+        let (version, payload) = unpack_account_data(data, &Self::DISCRIMINATOR)?;
         
-        msg!("Data size from header: {}", data_size);
+        msg!("Room layout version: {}, data size: {}", version, payload.len());
         
-        // Sprawdź czy mamy wystarczająco danych
-        if data.len() < Self::HEADER_SIZE + data_size {
-            msg!("Not enough data. Expected: {}, got: {}", Self::HEADER_SIZE + data_size, data.len());
-            return Err(ProgramError::InvalidAccountData);
+        // Każda wersja układu ma własną ścieżkę dekodowania. Po zmianie układu
+        // zwiększamy `VERSION`, a poprzednią wersję zamrażamy jako osobną strukturę
+        // i migrujemy ją tutaj, dzięki czemu pokoje już zapisane on-chain nadal się wczytują.
+        let game_room = match version {
+            1 => GameRoomV1::try_from_slice(payload).map(|room| room.migrate().migrate()),
+            2 => GameRoomV2::try_from_slice(payload).map(GameRoomV2::migrate),
+            Self::VERSION => Self::try_from_slice(payload),
+            _ => {
+                msg!("Error: Unsupported room layout version: {}", version);
+                return Err(UnoError::UnsupportedAccountVersion.into());
+            }
         }
+        .map_err(|e| {
+            msg!("Deserialization error: {:?}", e);
+            ProgramError::from(UnoError::InvalidRoomData)
+        })?;
         
-        // Deserializuj dane pomijając nagłówek
-        Self::try_from_slice(&data[Self::HEADER_SIZE..Self::HEADER_SIZE + data_size])
-            .map_err(|e| {
-                msg!("Deserialization error: {:?}", e);
-                ProgramError::InvalidAccountData
-            })
+        Ok((game_room, version))
     }
     
-    // Metoda do bezpiecznej serializacji
+    // Metoda do bezpiecznej serializacji - zawsze zapisuje bieżącą wersję układu
     pub fn to_account_data(&self, data: &mut [u8]) -> Result<(), ProgramError> {
-        // Sprawdzamy czy mamy wystarczająco miejsca
-        if data.len() < Self::SIZE {
-            return Err(ProgramError::AccountDataTooSmall);
-        }
-        
-        // Najpierw wyczyść cały bufor
-        for byte in data.iter_mut() {
-            *byte = 0;
-        }
-        
         // Serializuj do tymczasowego bufora
         let mut temp_buffer = Vec::new();
         self.serialize(&mut temp_buffer)?;
         
-        let data_size = temp_buffer.len();
-        msg!("Serialized data size: {}", data_size);
+        msg!("Serialized data size: {}", temp_buffer.len());
+        
+        pack_account_data(data, &Self::DISCRIMINATOR, Self::VERSION, &temp_buffer)
+    }
+    
+    // Dodaje gracza wraz z pustymi wpisami w listach równoległych do `players`
+    pub fn add_player(&mut self, player: Pubkey) {
+        self.players.push(player);
+        self.seed_commitments.push([0u8; 32]);
+        self.seeds_revealed.push(false);
+        self.ready.push(false);
+        self.clear_ready();
+    }
+    
+    // Usuwa gracza z listy, zwraca false jeśli go nie było
+    pub fn remove_player(&mut self, player: &Pubkey) -> bool {
+        match self.player_index(player) {
+            Some(index) => {
+                self.players.remove(index);
+                self.seed_commitments.remove(index);
+                self.seeds_revealed.remove(index);
+                self.ready.remove(index);
+                self.clear_ready();
+                // Gospodarz opuszczający pokój oddaje rolę twórcy, który zawsze w nim siedzi
+                if self.host == *player {
+                    self.host = self.creator;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    
+    // Przesuwa graczy, którzy się poddali, na ostatnie miejsca kolejności zakończenia gry
+    pub fn rank_forfeits_last(&self, placements: Vec<Pubkey>) -> Vec<Pubkey> {
+        placements
+            .into_iter()
+            .filter(|player| !self.forfeited.contains(player))
+            .chain(self.forfeited.iter().rev().copied())
+            .collect()
+    }
+    
+    // Gracze, którzy nadal biorą udział w grze
+    pub fn remaining_players(&self) -> Vec<Pubkey> {
+        self.players.iter().filter(|player| !self.forfeited.contains(player)).copied().collect()
+    }
+    
+    // Odnotowuje aktywność gospodarza - podpis gospodarza odsuwa przejęcie roli przez graczy
+    pub fn record_activity(&mut self, signer: &Pubkey, now: i64) {
+        if self.host == *signer {
+            self.host_active_at = now;
+        }
+    }
+    
+    // Zmiana składu unieważnia gotowość - gracze potwierdzają ją ponownie dla nowego składu
+    fn clear_ready(&mut self) {
+        self.ready.iter_mut().for_each(|ready| *ready = false);
+    }
+    
+    // Czy wystarczająco wielu graczy zgłosiło gotowość do startu
+    pub fn ready_to_start(&self) -> bool {
+        let ready_count = self.ready.iter().filter(|ready| **ready).count();
+        let required = match self.ready_quorum {
+            0 => self.players.len(),
+            quorum => quorum as usize,
+        };
+        ready_count >= required
+    }
+    
+    pub fn player_index(&self, player: &Pubkey) -> Option<usize> {
+        self.players.iter().position(|p| p == player)
+    }
+    
+    // Ustawia tabelę wypłat; pusta tabela oznacza, że całą pulę bierze zwycięzca.
+    // Udziały w punktach bazowych muszą być dodatnie, nierosnące i sumować się do 100%.
+    // Liczba płatnych miejsc jest tu ograniczona pojemnością pokoju, a `StartGame`
+    // wymaga, żeby graczy było co najmniej tyle, ile płatnych miejsc.
+    pub fn set_payout_table(&mut self, payout_bps: Vec<u16>) -> ProgramResult {
+        let payout_bps = if payout_bps.is_empty() { vec![WINNER_TAKES_ALL_BPS] } else { payout_bps };
         
-        // Sprawdź czy zmieści się w buforze
-        if data_size + Self::HEADER_SIZE > data.len() {
-            return Err(ProgramError::AccountDataTooSmall);
+        let total: u64 = payout_bps.iter().map(|bps| *bps as u64).sum();
+        let non_increasing = payout_bps.windows(2).all(|pair| pair[0] >= pair[1]);
+        if payout_bps.len() > self.max_players as usize
+            || payout_bps.contains(&0)
+            || !non_increasing
+            || total != BASIS_POINTS_DENOMINATOR
+        {
+            msg!("Error: Invalid payout table: {:?}", payout_bps);
+            return Err(UnoError::InvalidPayoutTable.into());
         }
         
-        // Zapisz rozmiar danych w pierwszych 4 bajtach
-        let size_bytes = (data_size as u32).to_le_bytes();
-        data[..4].copy_from_slice(&size_bytes);
+        self.payouts_claimed = vec![false; payout_bps.len()];
+        self.payout_bps = payout_bps;
+        Ok(())
+    }
+    
+    // Zapisuje kolejność zakończenia gry - musi obejmować każdego gracza dokładnie raz,
+    // a gracze, którzy się poddali, zajmują ostatnie miejsca (kto poddał się pierwszy, jest ostatni)
+    pub fn set_placements(&mut self, placements: Vec<Pubkey>) -> ProgramResult {
+        let is_permutation = placements.len() == self.players.len()
+            && self.players.iter().all(|player| placements.contains(player));
+        if !is_permutation {
+            msg!("Error: Finishing order must list every player exactly once");
+            return Err(UnoError::InvalidFinishingOrder.into());
+        }
         
-        // Zapisz dane po nagłówku
-        data[Self::HEADER_SIZE..Self::HEADER_SIZE + data_size].copy_from_slice(&temp_buffer);
+        let forfeits_last = placements.iter().rev().take(self.forfeited.len()).eq(self.forfeited.iter());
+        if !forfeits_last {
+            msg!("Error: Forfeited players {:?} must take the last places", self.forfeited);
+            return Err(UnoError::InvalidFinishingOrder.into());
+        }
         
+        self.winner = placements.first().copied();
+        self.placements = placements;
         Ok(())
     }
     
+    // Udział miejsca `place` w puli; zwycięzca dostaje też resztę z zaokrągleń,
+    // dzięki czemu suma udziałów zawsze równa się całej puli
+    pub fn payout_share(&self, place: usize, total_prize: u64) -> Result<u64, ProgramError> {
+        let share_of = |bps: u16| -> Result<u64, ProgramError> {
+            let share = (total_prize as u128)
+                .checked_mul(bps as u128)
+                .ok_or(UnoError::ArithmeticOverflow)?
+                / BASIS_POINTS_DENOMINATOR as u128;
+            Ok(share as u64)
+        };
+        
+        if place > 0 {
+            return share_of(self.payout_bps[place]);
+        }
+        
+        let mut others = 0u64;
+        for bps in &self.payout_bps[1..] {
+            others = others.checked_add(share_of(*bps)?).ok_or(UnoError::ArithmeticOverflow)?;
+        }
+        Ok(total_prize.checked_sub(others).ok_or(UnoError::ArithmeticOverflow)?)
+    }
+    
+    // Adres PDA skarbca tokenów pokoju wraz z bumpem
+    pub fn vault_address(program_id: &Pubkey, room: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[VAULT_SEED, room.as_ref()], program_id)
+    }
+    
+    // Po pierwszym ujawnieniu ziarna skład pokoju jest zamrożony
+    pub fn seed_reveal_started(&self) -> bool {
+        self.seeds_revealed.iter().any(|revealed| *revealed)
+    }
+    
+    // Przełącza pokój w stan gry: zapisuje ID gry, czas startu oraz ziarno talii
+    // z ziaren graczy, ostatniego hasha slotu i adresu pokoju - każdy może
+    // odtworzyć kolejność kart (`rules::shuffled_deck`), a zapis ruchów
+    // można później sprawdzić on-chain
+    pub fn start(&mut self, room: &Pubkey, game_id: &str, started_at: i64, recent_slot_hash: &[u8; 32]) {
+        self.status = GameStatus::InProgress;
+        self.set_game_id(game_id);
+        self.game_started_at = Some(started_at);
+        self.deck_seed = hashv(&[&self.seed_accumulator, recent_slot_hash, room.as_ref()]).to_bytes();
+    }
+    
     // Dodajemy metodę pomocniczą do ustawiania game_id
     pub fn set_game_id(&mut self, id: &str) {
         let bytes = id.as_bytes();
@@ -137,100 +678,517 @@ impl GameRoom {
     }
 }
 
+/// ID gry rozpoczętej automatycznie - wyznaczane z adresu pokoju, więc klienci znają je z góry
+pub fn auto_start_game_id(room: &Pubkey) -> String {
+    format!("sng-{}", room)
+}
+
+/// Zdarzenie startu gry zapisywane w logach transakcji przez `sol_log_data`
+/// (`Program data: <base64>` - dyskryminator, a po nim dane w borsh)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct GameStartedEvent {
+    pub room: Pubkey,
+    pub game_id: String,
+    pub players: Vec<Pubkey>,
+    pub deck_seed: [u8; 32],
+    pub started_at: i64,
+}
+
+impl GameStartedEvent {
+    pub const DISCRIMINATOR: [u8; 8] = *b"uno:strt";
+    
+    pub fn new(room: &Pubkey, game_room: &GameRoom) -> Self {
+        Self {
+            room: *room,
+            game_id: game_room.get_game_id(),
+            players: game_room.players.clone(),
+            deck_seed: game_room.deck_seed,
+            started_at: game_room.game_started_at.unwrap_or_default(),
+        }
+    }
+    
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&Self::DISCRIMINATOR, &self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+/// Parametry konfiguracji ustawiane przez administratora
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ConfigParams {
+    pub fee_recipient: Pubkey,            // Portfel platformy odbierający prowizję
+    pub fee_basis_points: u16,            // Prowizja w punktach bazowych (500 = 5%)
+    pub min_entry_fee_lamports: u64,      // Minimalne wpisowe
+    pub max_entry_fee_lamports: u64,      // Maksymalne wpisowe
+    pub max_players: u8,                  // Maksymalna liczba graczy w pokoju
+    pub result_authority: Pubkey,         // Serwer gry uprawniony do ogłaszania zwycięzcy
+    pub abandon_timeout_secs: i64,        // Czas od startu gry, po którym można odzyskać wpisowe
+    pub host_inactivity_secs: i64,        // Czas bez podpisu gospodarza, po którym gracz może przejąć jego rolę
+}
+
+impl ConfigParams {
+    // Walidacja spójności parametrów
+    pub fn validate(&self) -> ProgramResult {
+        if u64::from(self.fee_basis_points) > BASIS_POINTS_DENOMINATOR {
+            msg!("Error: Fee basis points out of range: {}", self.fee_basis_points);
+            return Err(UnoError::InvalidConfigParams.into());
+        }
+        
+        if self.min_entry_fee_lamports == 0 || self.min_entry_fee_lamports > self.max_entry_fee_lamports {
+            msg!("Error: Invalid entry fee range: {} - {}", self.min_entry_fee_lamports, self.max_entry_fee_lamports);
+            return Err(UnoError::InvalidConfigParams.into());
+        }
+        
+        if !(2..=MAX_PLAYERS_LIMIT).contains(&self.max_players) {
+            msg!("Error: Invalid max players in config: {}", self.max_players);
+            return Err(UnoError::InvalidConfigParams.into());
+        }
+        
+        if self.abandon_timeout_secs <= 0 {
+            msg!("Error: Abandon timeout must be positive: {}", self.abandon_timeout_secs);
+            return Err(UnoError::InvalidConfigParams.into());
+        }
+        
+        if self.host_inactivity_secs <= 0 {
+            msg!("Error: Host inactivity timeout must be positive: {}", self.host_inactivity_secs);
+            return Err(UnoError::InvalidConfigParams.into());
+        }
+        
+        Ok(())
+    }
+}
+
+/// Zamrożony układ konfiguracji w wersji 1 (przed `host_inactivity_secs`)
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ProgramConfigV1 {
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub fee_basis_points: u16,
+    pub min_entry_fee_lamports: u64,
+    pub max_entry_fee_lamports: u64,
+    pub max_players: u8,
+    pub result_authority: Pubkey,
+    pub abandon_timeout_secs: i64,
+    pub bump: u8,
+}
+
+impl ProgramConfigV1 {
+    // Migracja do bieżącego układu z domyślnym czasem nieaktywności gospodarza
+    fn migrate(self) -> ProgramConfig {
+        ProgramConfig {
+            admin: self.admin,
+            params: ConfigParams {
+                fee_recipient: self.fee_recipient,
+                fee_basis_points: self.fee_basis_points,
+                min_entry_fee_lamports: self.min_entry_fee_lamports,
+                max_entry_fee_lamports: self.max_entry_fee_lamports,
+                max_players: self.max_players,
+                result_authority: self.result_authority,
+                abandon_timeout_secs: self.abandon_timeout_secs,
+                host_inactivity_secs: DEFAULT_HOST_INACTIVITY_SECS,
+            },
+            bump: self.bump,
+        }
+    }
+}
+
+/// Globalna konfiguracja programu - singleton PDA `[b"uno_config"]`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,                    // 32 bajty - administrator konfiguracji
+    pub params: ConfigParams,             // 32 + 2 + 8 + 8 + 1 + 32 + 8 + 8 = 99 bajtów
+    pub bump: u8,                         // 1 bajt - bump PDA
+}
+
+impl ProgramConfig {
+    /// Rozmiar konta z zapasem na przyszłe pola konfiguracji
+    pub const SIZE: usize = 256;
+    pub const DISCRIMINATOR: [u8; 8] = *b"uno:conf";
+    /// Bieżąca wersja układu danych konfiguracji (wersja 1 to `ProgramConfigV1`)
+    pub const VERSION: u8 = 2;
+    
+    // Metoda do bezpiecznej deserializacji - starsze wersje układu są migrowane przy odczycie
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        let (version, payload) = unpack_account_data(data, &Self::DISCRIMINATOR)?;
+        
+        match version {
+            1 => ProgramConfigV1::try_from_slice(payload).map(ProgramConfigV1::migrate),
+            Self::VERSION => Self::try_from_slice(payload),
+            _ => {
+                msg!("Error: Unsupported config layout version: {}", version);
+                return Err(UnoError::UnsupportedAccountVersion.into());
+            }
+        }
+        .map_err(|e| {
+            msg!("Config deserialization error: {:?}", e);
+            UnoError::InvalidConfigAccount.into()
+        })
+    }
+    
+    // Metoda do bezpiecznej serializacji - zawsze zapisuje bieżącą wersję układu
+    pub fn to_account_data(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let mut temp_buffer = Vec::new();
+        self.serialize(&mut temp_buffer)?;
+        
+        pack_account_data(data, &Self::DISCRIMINATOR, Self::VERSION, &temp_buffer)
+    }
+    
+    // Wczytuje konfigurację, sprawdzając właściciela i adres PDA
+    pub fn load(program_id: &Pubkey, config_account: &AccountInfo) -> Result<Self, ProgramError> {
+        if config_account.owner != program_id {
+            msg!("Error: Config account is not owned by this program");
+            return Err(UnoError::InvalidConfigAccount.into());
+        }
+        
+        let config = Self::from_account_data(&config_account.data.borrow())?;
+        
+        let expected_pubkey = Pubkey::create_program_address(&[CONFIG_SEED, &[config.bump]], program_id)
+            .map_err(|_| UnoError::InvalidConfigAccount)?;
+        
+        if expected_pubkey != *config_account.key {
+            msg!("Error: Invalid config PDA. Expected: {}, Got: {}", expected_pubkey, config_account.key);
+            return Err(UnoError::InvalidConfigAccount.into());
+        }
+        
+        Ok(config)
+    }
+    
+    // Oblicza prowizję platformy od podanej puli
+    pub fn platform_fee(&self, total_prize: u64) -> Result<u64, ProgramError> {
+        let fee = u128::from(total_prize)
+            .checked_mul(u128::from(self.params.fee_basis_points))
+            .ok_or(UnoError::ArithmeticOverflow)?
+            / u128::from(BASIS_POINTS_DENOMINATOR);
+        u64::try_from(fee).map_err(|_| UnoError::ArithmeticOverflow.into())
+    }
+}
+
+/// Licznik pokojów twórcy - PDA `[b"uno_creator", twórca]`
+///
+/// Nonce kolejnego pokoju rośnie monotonicznie i nigdy nie jest używany ponownie,
+/// więc klienci znajdują wszystkie pokoje twórcy, licząc od zera do `next_room_nonce`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct CreatorState {
+    pub creator: Pubkey,                  // 32 bajty - twórca pokojów
+    pub next_room_nonce: u64,             // 8 bajtów - nonce następnego pokoju
+    pub bump: u8,                         // 1 bajt - bump PDA
+}
+
+impl CreatorState {
+    /// Rozmiar konta z zapasem na przyszłe pola
+    pub const SIZE: usize = 64;
+    pub const DISCRIMINATOR: [u8; 8] = *b"uno:crtr";
+    /// Bieżąca wersja układu danych licznika
+    pub const VERSION: u8 = 1;
+    
+    // Metoda do bezpiecznej deserializacji
+    pub fn from_account_data(data: &[u8]) -> Result<Self, ProgramError> {
+        let (version, payload) = unpack_account_data(data, &Self::DISCRIMINATOR)?;
+        
+        match version {
+            Self::VERSION => Self::try_from_slice(payload)
+                .map_err(|e| {
+                    msg!("Creator state deserialization error: {:?}", e);
+                    UnoError::InvalidCreatorState.into()
+                }),
+            _ => {
+                msg!("Error: Unsupported creator state layout version: {}", version);
+                Err(UnoError::UnsupportedAccountVersion.into())
+            }
+        }
+    }
+    
+    // Metoda do bezpiecznej serializacji - zawsze zapisuje bieżącą wersję układu
+    pub fn to_account_data(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        let mut temp_buffer = Vec::new();
+        self.serialize(&mut temp_buffer)?;
+        
+        pack_account_data(data, &Self::DISCRIMINATOR, Self::VERSION, &temp_buffer)
+    }
+    
+    /// Adres PDA licznika pokojów twórcy wraz z bumpem
+    pub fn address(program_id: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CREATOR_SEED, creator.as_ref()], program_id)
+    }
+}
+
 /// Instrukcje programu UNO - zaktualizowane dla modelu hybrydowego
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum UnoInstruction {
-    /// Tworzy nowy pokój gry
+    /// Tworzy nowy pokój gry.
+    /// Pokój z `RoomAccess::InviteHash` jest zamknięty tylko do pierwszego dołączenia - sekret
+    /// trafia jawnie do danych transakcji `JoinPrivateRoom` i od tej chwili może go użyć każdy.
     /// Accounts:
     /// 1. `[signer]` Twórca pokoju (płaci wpisowe)
     /// 2. `[writable]` PDA dla danych pokoju
     /// 3. `[]` System program
     /// 4. `[]` Rent sysvar
+    /// 5. `[]` PDA konfiguracji programu
+    /// 6. `[writable]` PDA licznika pokojów twórcy (tworzony przy pierwszym pokoju)
+    ///
+    /// Gdy podano `mint` (wpisowe w tokenie SPL):
+    /// 7. `[]` Mint tokena
+    /// 8. `[writable]` PDA skarbca tokenów pokoju
+    /// 9. `[writable]` Konto tokenowe twórcy
+    /// 10. `[]` SPL Token program
     CreateRoom {
         max_players: u8,
         entry_fee_lamports: u64,
-        room_slot: u8,  // Dodajemy slot pokoju
+        room_nonce: u64,  // Musi być równy `next_room_nonce` z licznika twórcy
+        mint: Option<Pubkey>,
+        payout_bps: Vec<u16>,  // Udziały kolejnych miejsc (pusta = całość dla zwycięzcy)
+        access: RoomAccess,  // Pokój publiczny, lista zaproszonych lub hash sekretu zaproszenia
+        auto_start: bool,  // Gra startuje przy dołączeniu ostatniego gracza (bez `StartGame` i bez commit-reveal ziaren)
     },
     
-    /// Dołącza do istniejącego pokoju
+    /// Dołącza do istniejącego pokoju (w pokoju ze starszym układem gracz dopłaca też czynsz
+    /// za powiększenie konta - zob. `instruction::with_room_resize`)
     /// Accounts:
     /// 1. `[signer]` Gracz dołączający (płaci wpisowe)
     /// 2. `[writable]` PDA dla danych pokoju
     /// 3. `[]` System program
+    ///
+    /// Dla pokoju z tokenem SPL:
+    /// 4. `[writable]` Konto tokenowe gracza
+    /// 5. `[writable]` PDA skarbca tokenów pokoju
+    /// 6. `[]` SPL Token program
+    ///
+    /// Dla pokoju z `auto_start` jako ostatnie konto:
+    /// + `[]` SlotHashes sysvar (wymagany przy dołączeniu, które zapełnia pokój)
     JoinRoom,
     
-    /// Rozpoczyna grę i zapisuje ID serwera off-chain
+    /// Rozpoczyna grę i zapisuje ID serwera off-chain (wymaga ujawnienia ziaren wszystkich graczy
+    /// oraz gotowości graczy - wszystkich lub kworum ustalonego przez twórcę)
     /// Accounts:
     /// 1. `[signer]` Gracz inicjujący grę (musi być w pokoju)
     /// 2. `[writable]` PDA dla danych pokoju
+    /// 3. `[]` SlotHashes sysvar
     StartGame {
         game_id: String,
     },
     
     /// Kończy grę i zapisuje zwycięzcę
     /// Accounts:
-    /// 1. `[signer]` Gracz lub serwer sygnalizujący koniec (musi być w pokoju)
+    /// 1. `[signer]` Serwer gry (`result_authority` pokoju) lub jeden z graczy
     /// 2. `[writable]` PDA dla danych pokoju
+    /// + `[signer]` Pozostali gracze - wymagani, gdy wynik nie jest podpisany przez serwer
+    ///   (bez serwera zwycięzcę można ogłosić tylko jednogłośnie)
     EndGame {
         winner: Pubkey,
     },
     
-    /// Odbiera nagrodę
+    /// Odbiera udział w nagrodzie za zajęte miejsce (pokój zamyka się po wypłacie wszystkich udziałów)
     /// Accounts:
-    /// 1. `[signer]` Zwycięzca odbierający nagrodę
+    /// 1. `[signer]` Gracz z miejscem objętym tabelą wypłat
     /// 2. `[writable]` PDA dla danych pokoju
     /// 3. `[]` System program
-    /// 4. `[writable]` Portfel platformy dla prowizji
+    /// 4. `[writable]` Portfel platformy dla prowizji (`fee_recipient` z konfiguracji)
+    /// 5. `[]` PDA konfiguracji programu
+    /// 6. `[writable]` Twórca pokoju (odbiera czynsz po zamknięciu konta pokoju)
+    ///
+    /// Dla pokoju z tokenem SPL:
+    /// 7. `[writable]` Konto tokenowe zwycięzcy
+    /// 8. `[writable]` PDA skarbca tokenów pokoju
+    /// 9. `[writable]` Powiązane konto tokenowe (ATA) portfela platformy
+    /// 10. `[]` SPL Token program
+    /// 11. `[writable]` Konto tokenowe twórcy (odbiera resztę tokenów przy zamknięciu skarbca -
+    ///     wymagane tylko przy ostatniej wypłacie)
     ClaimPrize,
     
-    /// Anuluje pokój i zwraca wpisowe wszystkim graczom
+    /// Anuluje pokój, zwraca wpisowe wszystkim graczom i zamyka konto pokoju
+    /// (czynsz i wpisowe twórcy wracają do twórcy, nawet gdy anuluje inny gospodarz)
     /// Accounts:
-    /// 1. `[signer]` Twórca pokoju
+    /// 1. `[signer, writable]` Gospodarz pokoju
     /// 2. `[writable]` PDA dla danych pokoju
     /// 3. `[]` System program
-    /// + Accounts dla każdego gracza, któremu należy zwrócić wpisowe
+    /// + `[writable]` Konta pozostałych graczy w kolejności z `players` (bez gospodarza)
+    ///
+    /// Dla pokoju z tokenem SPL zamiast kont graczy:
+    /// 4. `[]` SPL Token program
+    /// 5. `[writable]` PDA skarbca tokenów pokoju
+    /// 6. `[writable]` Twórca pokoju (odbiera czynsz)
+    /// + `[writable]` Konta tokenowe wszystkich graczy w kolejności z `players`
     CancelRoom,
-}
-
-// Punkt wejścia programu
-entrypoint!(process_instruction);
-
-/// Przetwarzanie instrukcji programu
-pub fn process_instruction(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    input: &[u8],
-) -> ProgramResult {
-    msg!("Program called with {} bytes of data", input.len());
-    msg!("Data: {:?}", input);
     
-    // Sprawdź, czy mamy wystarczająco danych
-    if input.is_empty() {
-        msg!("Error: No instruction data provided");
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    /// Tworzy globalną konfigurację programu (jednorazowo, podpisujący zostaje administratorem).
+    /// Podpisać musi upgrade authority programu - inaczej konfigurację przejąłby pierwszy chętny.
+    /// Accounts:
+    /// 1. `[signer, writable]` Administrator (płaci za konto, upgrade authority programu)
+    /// 2. `[writable]` PDA konfiguracji programu
+    /// 3. `[]` System program
+    /// 4. `[]` Konto ProgramData programu (BPF Loader Upgradeable)
+    InitializeConfig {
+        params: ConfigParams,
+    },
     
-    // Sprawdź pierwszy bajt (instruction tag)
-    msg!("Instruction tag: {}", input[0]);
+    /// Aktualizuje konfigurację programu
+    /// Accounts:
+    /// 1. `[signer]` Obecny administrator
+    /// 2. `[writable]` PDA konfiguracji programu
+    UpdateConfig {
+        new_admin: Pubkey,
+        params: ConfigParams,
+    },
     
-    let instruction = UnoInstruction::try_from_slice(input)?;
+    /// Zwraca wpisowe wszystkim graczom porzuconej gry i zamyka pokój
+    /// (dozwolone po `abandon_timeout_secs` od rozpoczęcia gry)
+    /// Accounts:
+    /// 1. `[signer]` Dowolny gracz z pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    /// 3. `[]` PDA konfiguracji programu
+    /// + `[writable]` Konta wszystkich graczy w kolejności z `players` (twórca odbiera też czynsz)
+    ///
+    /// Dla pokoju z tokenem SPL zamiast kont graczy:
+    /// 4. `[]` SPL Token program
+    /// 5. `[writable]` PDA skarbca tokenów pokoju
+    /// 6. `[writable]` Twórca pokoju (odbiera czynsz)
+    /// + `[writable]` Konta tokenowe wszystkich graczy w kolejności z `players`
+    ReclaimAbandoned,
+    
+    /// Opuszcza pokój przed rozpoczęciem gry i odbiera wpisowe (nie dotyczy twórcy)
+    /// Accounts:
+    /// 1. `[signer, writable]` Gracz opuszczający pokój
+    /// 2. `[writable]` PDA dla danych pokoju
+    ///
+    /// Dla pokoju z tokenem SPL:
+    /// 3. `[]` SPL Token program
+    /// 4. `[writable]` PDA skarbca tokenów pokoju
+    /// 5. `[writable]` Konto tokenowe gracza
+    LeaveRoom,
+    
+    /// Kończy grę na podstawie zapisu ruchów - program odtwarza rozgrywkę na talii
+    /// z `deck_seed` pokoju i sam ustala zwycięzcę (`rules::encode_moves`).
+    /// Gracze po `Forfeit` nie biorą udziału w odtwarzanej grze - zapis obejmuje tylko
+    /// pozostałych graczy (w kolejności z `players`), a poddani zajmują ostatnie miejsca.
+    /// Accounts:
+    /// 1. `[signer]` Serwer gry (`result_authority` pokoju) lub jeden z graczy
+    /// 2. `[writable]` PDA dla danych pokoju
+    /// + `[signer]` Pozostali gracze - wymagani, gdy zapis nie jest podpisany przez serwer
+    SettleWithTranscript {
+        moves: Vec<u8>,
+    },
     
-    match instruction {
-        UnoInstruction::CreateRoom { max_players, entry_fee_lamports, room_slot } => {
-            msg!("Processing CreateRoom: max_players={}, entry_fee={}, slot={}", max_players, entry_fee_lamports, room_slot);
-            process_create_room(program_id, accounts, max_players, entry_fee_lamports, room_slot)
-        },
-        UnoInstruction::JoinRoom => {
-            msg!("Processing JoinRoom");
-            process_join_room(program_id, accounts)
-        },
-        UnoInstruction::StartGame { game_id } => {
-            msg!("Processing StartGame with id: {}", game_id);
-            process_start_game(program_id, accounts, game_id)
-        },
-        UnoInstruction::EndGame { winner } => {
-            msg!("Processing EndGame with winner: {}", winner);
-            process_end_game(program_id, accounts, winner)
+    /// Zapisuje zobowiązanie gracza do ziarna talii (`seed_commitment`)
+    /// Accounts:
+    /// 1. `[signer]` Gracz z pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    CommitSeed {
+        commitment: [u8; 32],
+    },
+    
+    /// Ujawnia ziarno gracza - dozwolone, gdy wszyscy gracze złożyli zobowiązania
+    /// Accounts:
+    /// 1. `[signer]` Gracz z pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    RevealSeed {
+        seed: [u8; 32],
+    },
+    
+    /// Kończy grę i zapisuje pełną kolejność miejsc (pierwszy na liście jest zwycięzcą).
+    /// Gracz, który się poddał, nie może zająć żadnego płatnego miejsca.
+    /// Accounts:
+    /// 1. `[signer]` Serwer gry (`result_authority` pokoju) lub jeden z graczy
+    /// 2. `[writable]` PDA dla danych pokoju
+    /// + `[signer]` Pozostali gracze - wymagani, gdy wynik nie jest podpisany przez serwer
+    EndGameRanked {
+        placements: Vec<Pubkey>,
+    },
+    
+    /// Zgłasza lub wycofuje gotowość gracza do startu gry
+    /// Accounts:
+    /// 1. `[signer]` Gracz z pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    SetReady {
+        ready: bool,
+    },
+    
+    /// Ustawia liczbę gotowych graczy wymaganą do startu (0 = wszyscy gracze w pokoju)
+    /// Accounts:
+    /// 1. `[signer]` Gospodarz pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    SetReadyQuorum {
+        quorum: u8,
+    },
+    
+    /// Wyrzuca gracza z pokoju przed rozpoczęciem gry i zwraca mu wpisowe
+    /// (z `ban` gracz trafia na listę zbanowanych i nie może ponownie dołączyć)
+    /// Accounts:
+    /// 1. `[signer]` Gospodarz pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    /// 3. `[writable]` Wyrzucany gracz (odbiera zwrot wpisowego)
+    ///
+    /// Dla pokoju z tokenem SPL:
+    /// 4. `[]` SPL Token program
+    /// 5. `[writable]` PDA skarbca tokenów pokoju
+    /// 6. `[writable]` Konto tokenowe wyrzucanego gracza
+    KickPlayer {
+        player: Pubkey,
+        ban: bool,
+    },
+    
+    /// Przekazuje rolę gospodarza innemu graczowi z pokoju (adres PDA pokoju się nie zmienia).
+    /// Gdy gospodarz jest nieaktywny dłużej niż `host_inactivity_secs`, rolę może przekazać każdy gracz.
+    /// Accounts:
+    /// 1. `[signer]` Gospodarz pokoju lub gracz z pokoju (po czasie nieaktywności gospodarza)
+    /// 2. `[writable]` PDA dla danych pokoju
+    /// 3. `[]` PDA konfiguracji programu
+    TransferHost {
+        new_host: Pubkey,
+    },
+    
+    /// Gracz poddaje trwającą grę i zajmuje najniższe wolne miejsce.
+    /// Gdy w grze zostaje jeden gracz, gra kończy się automatycznie z nim jako zwycięzcą.
+    /// Accounts:
+    /// 1. `[signer]` Gracz z pokoju
+    /// 2. `[writable]` PDA dla danych pokoju
+    Forfeit,
+    
+    /// Dołącza do pokoju prywatnego, przedstawiając sekret zaproszenia (`RoomAccess::InviteHash`)
+    /// Accounts: jak w `JoinRoom`
+    JoinPrivateRoom {
+        invite_secret: Vec<u8>,
+    },
+}
+
+// Punkt wejścia programu (wyłączany przez klientów korzystających z crate'a jako biblioteki)
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+
+/// Przetwarzanie instrukcji programu
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    msg!("Program called with {} bytes of data", input.len());
+    
+    // Sprawdź, czy mamy wystarczająco danych
+    if input.is_empty() {
+        msg!("Error: No instruction data provided");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    
+    // Sprawdź pierwszy bajt (instruction tag)
+    msg!("Instruction tag: {}", input[0]);
+    
+    let instruction = UnoInstruction::try_from_slice(input)?;
+    
+    match instruction {
+        UnoInstruction::CreateRoom { max_players, entry_fee_lamports, room_nonce, mint, payout_bps, access, auto_start } => {
+            msg!("Processing CreateRoom: max_players={}, entry_fee={}, nonce={}, mint={:?}, payouts={:?}, auto_start={}", max_players, entry_fee_lamports, room_nonce, mint, payout_bps, auto_start);
+            process_create_room(program_id, accounts, max_players, entry_fee_lamports, room_nonce, mint, payout_bps, access, auto_start)
+        },
+        UnoInstruction::JoinRoom => {
+            msg!("Processing JoinRoom");
+            process_join_room(program_id, accounts, None)
+        },
+        UnoInstruction::StartGame { game_id } => {
+            msg!("Processing StartGame with id: {}", game_id);
+            process_start_game(program_id, accounts, game_id)
+        },
+        UnoInstruction::EndGame { winner } => {
+            msg!("Processing EndGame with winner: {}", winner);
+            process_end_game(program_id, accounts, winner)
         },
         UnoInstruction::ClaimPrize => {
             msg!("Processing ClaimPrize");
@@ -240,18 +1198,79 @@ pub fn process_instruction(
             msg!("Processing CancelRoom");
             process_cancel_room(program_id, accounts)
         },
+        UnoInstruction::InitializeConfig { params } => {
+            msg!("Processing InitializeConfig");
+            process_initialize_config(program_id, accounts, params)
+        },
+        UnoInstruction::UpdateConfig { new_admin, params } => {
+            msg!("Processing UpdateConfig: new_admin={}", new_admin);
+            process_update_config(program_id, accounts, new_admin, params)
+        },
+        UnoInstruction::ReclaimAbandoned => {
+            msg!("Processing ReclaimAbandoned");
+            process_reclaim_abandoned(program_id, accounts)
+        },
+        UnoInstruction::LeaveRoom => {
+            msg!("Processing LeaveRoom");
+            process_leave_room(program_id, accounts)
+        },
+        UnoInstruction::SettleWithTranscript { moves } => {
+            msg!("Processing SettleWithTranscript: {} bytes", moves.len());
+            process_settle_with_transcript(program_id, accounts, moves)
+        },
+        UnoInstruction::CommitSeed { commitment } => {
+            msg!("Processing CommitSeed");
+            process_commit_seed(program_id, accounts, commitment)
+        },
+        UnoInstruction::RevealSeed { seed } => {
+            msg!("Processing RevealSeed");
+            process_reveal_seed(program_id, accounts, seed)
+        },
+        UnoInstruction::EndGameRanked { placements } => {
+            msg!("Processing EndGameRanked: {:?}", placements);
+            process_end_game_ranked(program_id, accounts, placements)
+        },
+        UnoInstruction::SetReady { ready } => {
+            msg!("Processing SetReady: {}", ready);
+            process_set_ready(program_id, accounts, ready)
+        },
+        UnoInstruction::SetReadyQuorum { quorum } => {
+            msg!("Processing SetReadyQuorum: {}", quorum);
+            process_set_ready_quorum(program_id, accounts, quorum)
+        },
+        UnoInstruction::KickPlayer { player, ban } => {
+            msg!("Processing KickPlayer: {}, ban={}", player, ban);
+            process_kick_player(program_id, accounts, player, ban)
+        },
+        UnoInstruction::TransferHost { new_host } => {
+            msg!("Processing TransferHost: {}", new_host);
+            process_transfer_host(program_id, accounts, new_host)
+        },
+        UnoInstruction::Forfeit => {
+            msg!("Processing Forfeit");
+            process_forfeit(program_id, accounts)
+        },
+        UnoInstruction::JoinPrivateRoom { invite_secret } => {
+            msg!("Processing JoinPrivateRoom");
+            process_join_room(program_id, accounts, Some(&invite_secret))
+        },
     }
 }
 
 /// Implementacja tworzenia pokoju
+#[allow(clippy::too_many_arguments)] // Parametry odpowiadają polom wariantu `CreateRoom`
 fn process_create_room(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     max_players: u8,
     entry_fee_lamports: u64,
-    room_slot: u8,
+    room_nonce: u64,
+    mint: Option<Pubkey>,
+    payout_bps: Vec<u16>,
+    access: RoomAccess,
+    auto_start: bool,
 ) -> ProgramResult {
-    msg!("Starting create_room with max_players: {}, entry_fee: {}, slot: {}", max_players, entry_fee_lamports, room_slot);
+    msg!("Starting create_room with max_players: {}, entry_fee: {}, nonce: {}", max_players, entry_fee_lamports, room_nonce);
     
     let accounts_iter = &mut accounts.iter();
     
@@ -267,460 +1286,1838 @@ fn process_create_room(
     let rent_account = next_account_info(accounts_iter)?;
     msg!("Rent account: {}", rent_account.key);
     
+    let config_account = next_account_info(accounts_iter)?;
+    msg!("Config account: {}", config_account.key);
+    
+    let creator_state_account = next_account_info(accounts_iter)?;
+    msg!("Creator state account: {}", creator_state_account.key);
+    
     // Sprawdź, czy to rzeczywiście system program
-    if *system_program.key != solana_program::system_program::ID {
-        msg!("Error: Invalid system program account");
-        return Err(ProgramError::InvalidArgument);
-    }
+    check_system_program(system_program)?;
     
     // Weryfikacja podpisu
     if !creator_account.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Walidacja parametrów
-    if max_players < 2 || max_players > 4 {
-        return Err(ProgramError::InvalidArgument);
+    // Walidacja parametrów względem konfiguracji programu
+    let config = ProgramConfig::load(program_id, config_account)?;
+    
+    if max_players < 2 || max_players > config.params.max_players {
+        msg!("Error: Invalid max players: {}", max_players);
+        return Err(UnoError::InvalidMaxPlayers.into());
     }
     
-    if entry_fee_lamports == 0 {
-        return Err(ProgramError::InvalidArgument);
+    // Limity wpisowego z konfiguracji są w lamportach; dla tokenów SPL wymagamy tylko niezerowej kwoty
+    let fee_out_of_range = match mint {
+        None => entry_fee_lamports < config.params.min_entry_fee_lamports
+            || entry_fee_lamports > config.params.max_entry_fee_lamports,
+        Some(_) => entry_fee_lamports == 0,
+    };
+    if fee_out_of_range {
+        msg!("Error: Entry fee {} outside allowed range {} - {}",
+            entry_fee_lamports, config.params.min_entry_fee_lamports, config.params.max_entry_fee_lamports);
+        return Err(UnoError::InvalidEntryFee.into());
     }
     
-    // Walidacja slotu (maksymalnie 10 pokojów na użytkownika)
-    if room_slot >= 10 {
-        msg!("Error: Invalid room slot: {}", room_slot);
-        return Err(ProgramError::InvalidArgument);
+    access.validate()?;
+    
+    let rent = Rent::from_account_info(rent_account)?;
+    
+    // Licznik pokojów twórcy - tworzony przy pierwszym pokoju, później tylko zwiększany
+    let (expected_state_pubkey, state_bump) = CreatorState::address(program_id, creator_account.key);
+    if expected_state_pubkey != *creator_state_account.key {
+        msg!("Error: Invalid creator state PDA. Expected: {}, Got: {}", expected_state_pubkey, creator_state_account.key);
+        return Err(UnoError::InvalidCreatorState.into());
+    }
+    
+    let mut creator_state = if creator_state_account.owner == program_id {
+        CreatorState::from_account_data(&creator_state_account.data.borrow())?
+    } else {
+        create_pda_account(
+            creator_account,
+            creator_state_account,
+            system_program,
+            &rent,
+            CreatorState::SIZE,
+            program_id,
+            &[CREATOR_SEED, creator_account.key.as_ref(), &[state_bump]],
+        )?;
+        CreatorState {
+            creator: *creator_account.key,
+            next_room_nonce: 0,
+            bump: state_bump,
+        }
+    };
+    
+    if room_nonce != creator_state.next_room_nonce {
+        msg!("Error: Stale room nonce {}, next nonce is {}", room_nonce, creator_state.next_room_nonce);
+        return Err(UnoError::StaleRoomNonce.into());
     }
     
-    // Weryfikacja czy konto pokoju jest prawidłowym PDA z uwzględnieniem slotu
+    // Weryfikacja czy konto pokoju jest prawidłowym PDA z uwzględnieniem nonce
+    let nonce_seed = room_nonce.to_le_bytes();
     let (expected_game_pubkey, bump_seed) = Pubkey::find_program_address(
-        &[b"uno_game", creator_account.key.as_ref(), &[room_slot]],
+        &[ROOM_SEED, creator_account.key.as_ref(), &nonce_seed],
         program_id,
     );
     
     if expected_game_pubkey != *game_account.key {
         msg!("Error: Invalid PDA. Expected: {}, Got: {}", expected_game_pubkey, game_account.key);
-        return Err(ProgramError::InvalidArgument);
+        return Err(UnoError::InvalidRoomAddress.into());
     }
     
-    // Obliczenie czynszu
-    let rent = Rent::from_account_info(rent_account)?;
-    let space = GameRoom::SIZE;
-    let lamports = rent.minimum_balance(space);
-    
     // Utworzenie konta PDA
-    invoke_signed(
-        &system_instruction::create_account(
-            creator_account.key,
-            game_account.key,
-            lamports,
-            space as u64,
-            program_id,
-        ),
-        &[
-            creator_account.clone(),
-            game_account.clone(),
-            system_program.clone(),
-        ],
-        &[&[b"uno_game", creator_account.key.as_ref(), &[room_slot], &[bump_seed]]],
+    let space = GameRoom::space_with_access(max_players, &access);
+    create_pda_account(
+        creator_account,
+        game_account,
+        system_program,
+        &rent,
+        space,
+        program_id,
+        &[ROOM_SEED, creator_account.key.as_ref(), &nonce_seed, &[bump_seed]],
     )?;
     
+    // Inicjalizacja danych pokoju
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+    
+    let mut game_room = GameRoom::new(
+        *creator_account.key,
+        max_players,
+        entry_fee_lamports,
+        current_timestamp,
+        room_nonce,
+        config.params.result_authority,
+        bump_seed,
+    );
+    game_room.set_payout_table(payout_bps)?;
+    game_room.access = access;
+    game_room.auto_start = auto_start;
+    
+    match mint {
+        None => {
+            // Transfer wpisowego
+            invoke(
+                &system_instruction::transfer(
+                    creator_account.key,
+                    game_account.key,
+                    entry_fee_lamports,
+                ),
+                &[
+                    creator_account.clone(),
+                    game_account.clone(),
+                    system_program.clone(),
+                ],
+            )?;
+        }
+        Some(mint) => {
+            let mint_account = next_account_info(accounts_iter)?;
+            let vault_account = next_account_info(accounts_iter)?;
+            let creator_token_account = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            
+            if *mint_account.key != mint {
+                msg!("Error: Mint account {} does not match {}", mint_account.key, mint);
+                return Err(UnoError::InvalidTokenAccount.into());
+            }
+            
+            let (expected_vault, vault_bump) = GameRoom::vault_address(program_id, game_account.key);
+            if expected_vault != *vault_account.key {
+                msg!("Error: Invalid vault PDA. Expected: {}, Got: {}", expected_vault, vault_account.key);
+                return Err(UnoError::InvalidVault.into());
+            }
+            game_room.mint = Some(mint);
+            game_room.vault_bump = vault_bump;
+            check_token_program(token_program)?;
+            
+            // Skarb tokenów należy do SPL Token, a jego właścicielem jest PDA pokoju
+            create_pda_account(
+                creator_account,
+                vault_account,
+                system_program,
+                &rent,
+                TokenAccount::LEN,
+                token_program.key,
+                &[VAULT_SEED, game_account.key.as_ref(), &[vault_bump]],
+            )?;
+            invoke(
+                &spl_token::instruction::initialize_account3(
+                    token_program.key,
+                    vault_account.key,
+                    mint_account.key,
+                    game_account.key,
+                )?,
+                &[vault_account.clone(), mint_account.clone()],
+            )?;
+            
+            // Transfer wpisowego w tokenach
+            check_token_account(creator_token_account, creator_account.key, &mint)?;
+            invoke(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    creator_token_account.key,
+                    vault_account.key,
+                    creator_account.key,
+                    &[],
+                    entry_fee_lamports,
+                )?,
+                &[
+                    creator_token_account.clone(),
+                    vault_account.clone(),
+                    creator_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+    }
+    
+    // Serializacja i zapisanie danych
+    game_room.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    creator_state.next_room_nonce = room_nonce.checked_add(1).ok_or(UnoError::ArithmeticOverflow)?;
+    creator_state.to_account_data(&mut creator_state_account.data.borrow_mut())?;
+    
+    msg!("Utworzono nowy pokój gry UNO z nonce {}", room_nonce);
+    Ok(())
+}
+
+/// Implementacja dołączania do pokoju
+fn process_join_room(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    invite_secret: Option<&[u8]>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let player_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    
+    msg!("Join room - Player: {}", player_account.key);
+    msg!("Join room - Game account: {}", game_account.key);
+    
+    // Weryfikacja podpisu
+    if !player_account.is_signer {
+        msg!("Error: Missing required signature");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    check_system_program(system_program)?;
+    
+    // Sprawdzenie rozmiaru konta przed deserializacją
+    msg!("Game account data length: {}", game_account.data_len());
+    
+    // Wczytanie danych pokoju
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    msg!("Game room loaded successfully");
+    msg!("Current players: {:?}", game_room.players);
+    msg!("Entry fee: {}", game_room.entry_fee_lamports);
+    
+    // Sprawdzenie stanu pokoju
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
+    
+    // Sprawdzenie czy gracz już jest w pokoju
+    if game_room.players.contains(player_account.key) {
+        msg!("Error: Player already in room");
+        return Err(UnoError::PlayerAlreadyJoined.into());
+    }
+    
+    if game_room.banned.contains(player_account.key) {
+        msg!("Error: Player {} is banned from this room", player_account.key);
+        return Err(UnoError::PlayerBanned.into());
+    }
+    
+    // Pokój prywatny - gracz musi być na liście lub znać sekret zaproszenia
+    game_room.access.admit(player_account.key, invite_secret)?;
+    
+    // Po rozpoczęciu ujawniania ziaren nowy gracz mógłby dobrać swoje ziarno pod talię
+    if game_room.seed_reveal_started() {
+        msg!("Error: Seed reveal already started");
+        return Err(UnoError::SeedRevealInProgress.into());
+    }
+    
+    // Sprawdzenie czy pokój nie jest już pełny
+    if game_room.players.len() >= game_room.max_players as usize {
+        msg!("Error: Room is full");
+        return Err(UnoError::RoomFull.into());
+    }
+    
+    msg!("Transferring entry fee: {}", game_room.entry_fee_lamports);
+    
     // Transfer wpisowego
-    invoke(
-        &system_instruction::transfer(
-            creator_account.key,
-            game_account.key,
-            entry_fee_lamports,
-        ),
-        &[
-            creator_account.clone(),
-            game_account.clone(),
-            system_program.clone(),
-        ],
-    )?;
+    match game_room.mint {
+        None => invoke(
+            &system_instruction::transfer(
+                player_account.key,
+                game_account.key,
+                game_room.entry_fee_lamports,
+            ),
+            &[
+                player_account.clone(),
+                game_account.clone(),
+                system_program.clone(),
+            ],
+        )?,
+        Some(mint) => {
+            let player_token_account = next_account_info(accounts_iter)?;
+            let vault_account = next_account_info(accounts_iter)?;
+            let token_program = next_account_info(accounts_iter)?;
+            
+            check_room_vault(program_id, &game_room, game_account, vault_account, token_program)?;
+            check_token_account(player_token_account, player_account.key, &mint)?;
+            invoke(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    player_token_account.key,
+                    vault_account.key,
+                    player_account.key,
+                    &[],
+                    game_room.entry_fee_lamports,
+                )?,
+                &[
+                    player_token_account.clone(),
+                    vault_account.clone(),
+                    player_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+    }
+    
+    msg!("Entry fee transferred successfully");
+    
+    // Dodanie gracza do listy
+    game_room.add_player(*player_account.key);
+    
+    msg!("Player added to room. Total players: {}", game_room.players.len());
+    
+    // Pokój "sit and go" - ostatnie wolne miejsce rozpoczyna grę bez osobnego `StartGame`.
+    // Taki pokój pomija commit-reveal ziaren: talię wyznacza tylko hash ostatniego slotu
+    // (SlotHashes) i adres pokoju, więc jest tak nieprzewidywalna jak hash slotu.
+    let auto_started = game_room.auto_start && game_room.players.len() == game_room.max_players as usize;
+    if auto_started {
+        let slot_hashes_account = next_account_info(accounts_iter)?;
+        let recent_slot_hash = read_recent_slot_hash(slot_hashes_account)?;
+        let clock = Clock::get()?;
+        game_room.start(game_account.key, &auto_start_game_id(game_account.key), clock.unix_timestamp, &recent_slot_hash);
+    }
+    
+    // Zapisanie zaktualizowanych danych
+    store_room(&game_room, game_account, player_account, accounts)?;
+    
+    msg!("Dołączono do pokoju gry UNO");
+    if auto_started {
+        GameStartedEvent::new(game_account.key, &game_room).emit()?;
+        msg!("Gra UNO rozpoczęta automatycznie. Off-chain ID: {}", game_room.get_game_id());
+    }
+    Ok(())
+}
+
+/// Implementacja rozpoczęcia gry
+fn process_start_game(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    game_id: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let initiator_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let slot_hashes_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !initiator_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Wczytanie danych pokoju
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    // Sprawdzenie czy osoba inicjująca jest w pokoju
+    if !game_room.players.contains(initiator_account.key) {
+        msg!("Error: Initiator is not a player in this room");
+        return Err(UnoError::NotAPlayer.into());
+    }
+    
+    // Sprawdzenie stanu pokoju
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
+    
+    // Pokój z auto-startem rusza sam po zapełnieniu
+    if game_room.auto_start {
+        msg!("Error: Auto-start room starts when the last seat is taken");
+        return Err(UnoError::AutoStartRoom.into());
+    }
+    
+    // Sprawdzenie minimalnej liczby graczy
+    if game_room.players.len() < 2 {
+        msg!("Error: Not enough players to start");
+        return Err(UnoError::NotEnoughPlayers.into());
+    }
+    
+    // Każde miejsce z tabeli wypłat musi mieć gracza - inaczej nieodebranego udziału
+    // nie dałoby się wypłacić, a pokój nigdy by się nie zamknął
+    if game_room.players.len() < game_room.payout_bps.len() {
+        msg!("Error: {} players cannot fill {} paid places", game_room.players.len(), game_room.payout_bps.len());
+        return Err(UnoError::NotEnoughPlayers.into());
+    }
+    
+    // Start dopiero, gdy gracze (lub ustalone przez twórcę kworum) potwierdzili gotowość
+    if !game_room.ready_to_start() {
+        msg!("Error: Players not ready: {:?}, quorum: {}", game_room.ready, game_room.ready_quorum);
+        return Err(UnoError::PlayersNotReady.into());
+    }
+    
+    // Talia powstaje z ziaren wszystkich graczy
+    if !game_room.seeds_revealed.iter().all(|revealed| *revealed) {
+        msg!("Error: Not all players have revealed their seeds");
+        return Err(UnoError::SeedsNotRevealed.into());
+    }
+    
+    let recent_slot_hash = read_recent_slot_hash(slot_hashes_account)?;
+    
+    // Ustawienie statusu gry, ID gry off-chain, czasu rozpoczęcia i ziarna talii
+    let clock = Clock::get()?;
+    game_room.record_activity(initiator_account.key, clock.unix_timestamp);
+    game_room.start(game_account.key, &game_id, clock.unix_timestamp, &recent_slot_hash);
+    
+    // Zapisanie zaktualizowanych danych
+    store_room(&game_room, game_account, initiator_account, accounts)?;
+    
+    GameStartedEvent::new(game_account.key, &game_room).emit()?;
+    msg!("Gra UNO rozpoczęta. Off-chain ID: {}", game_room.get_game_id());
+    Ok(())
+}
+
+/// Implementacja zakończenia gry
+fn process_end_game(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    winner: Pubkey,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let initiator_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !initiator_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Wczytanie danych pokoju
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    // Wynik ogłasza serwer gry albo wszyscy gracze jednogłośnie
+    check_result_approval(&game_room, initiator_account, accounts_iter.as_slice())?;
+    
+    // Sprawdzenie stanu pokoju
+    if game_room.status != GameStatus::InProgress {
+        msg!("Error: Game is not in progress");
+        return Err(UnoError::GameNotInProgress.into());
+    }
+    
+    // Sprawdzenie czy zwycięzca jest jednym z graczy
+    if !game_room.players.contains(&winner) {
+        msg!("Error: Winner is not a player in this room");
+        return Err(UnoError::WinnerNotAPlayer.into());
+    }
+    
+    if game_room.forfeited.contains(&winner) {
+        msg!("Error: Winner {} has forfeited the game", winner);
+        return Err(UnoError::PlayerForfeited.into());
+    }
+    
+    // Przy wypłacie dla kilku miejsc sam zwycięzca nie wystarcza
+    if game_room.payout_bps.len() > 1 {
+        msg!("Error: Room pays {} places, use EndGameRanked", game_room.payout_bps.len());
+        return Err(UnoError::FinishingOrderRequired.into());
+    }
+    
+    // Ustawienie zakończenia gry i zwycięzcy
+    game_room.status = GameStatus::Completed;
+    game_room.winner = Some(winner);
+    game_room.placements = vec![winner];
+    
+    // Zapisanie czasu zakończenia
+    let clock = Clock::get()?;
+    game_room.game_ended_at = Some(clock.unix_timestamp);
+    
+    // Zapisanie zaktualizowanych danych
+    store_room(&game_room, game_account, initiator_account, accounts)?;
+    
+    msg!("Gra UNO zakończona. Zwycięzca: {}", winner);
+    Ok(())
+}
+
+/// Implementacja zakończenia gry z pełną kolejnością miejsc
+fn process_end_game_ranked(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    placements: Vec<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let initiator_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !initiator_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Wczytanie danych pokoju
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    // Wynik ogłasza serwer gry albo wszyscy gracze jednogłośnie
+    check_result_approval(&game_room, initiator_account, accounts_iter.as_slice())?;
+    
+    // Sprawdzenie stanu pokoju
+    if game_room.status != GameStatus::InProgress {
+        msg!("Error: Game is not in progress");
+        return Err(UnoError::GameNotInProgress.into());
+    }
+    
+    // Tak jak w `EndGame` - poddany gracz nie dostaje udziału w puli
+    let paid_places = game_room.payout_bps.len();
+    if let Some(player) = placements.iter().take(paid_places).find(|player| game_room.forfeited.contains(player)) {
+        msg!("Error: Forfeited player {} cannot take a paid place", player);
+        return Err(UnoError::PlayerForfeited.into());
+    }
+    
+    // Ustawienie zakończenia gry i kolejności miejsc
+    game_room.status = GameStatus::Completed;
+    game_room.set_placements(placements)?;
+    
+    // Zapisanie czasu zakończenia
+    let clock = Clock::get()?;
+    game_room.game_ended_at = Some(clock.unix_timestamp);
+    
+    // Zapisanie zaktualizowanych danych
+    store_room(&game_room, game_account, initiator_account, accounts)?;
+    
+    msg!("Gra UNO zakończona. Kolejność miejsc: {:?}", game_room.placements);
+    Ok(())
+}
+
+/// Implementacja zakończenia gry na podstawie zapisu ruchów
+fn process_settle_with_transcript(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    moves: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let initiator_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !initiator_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Wczytanie danych pokoju
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    // Zapis ruchów podpisuje serwer gry albo wszyscy gracze
+    check_result_approval(&game_room, initiator_account, accounts_iter.as_slice())?;
+    
+    // Sprawdzenie stanu pokoju
+    if game_room.status != GameStatus::InProgress {
+        msg!("Error: Game is not in progress");
+        return Err(UnoError::GameNotInProgress.into());
+    }
+    
+    // Odtworzenie rozgrywki - każdy ruch musi być zgodny z zasadami.
+    // Gracze, którzy się poddali, nie siedzą już przy stole, więc zapis obejmuje tylko pozostałych.
+    let seats = game_room.remaining_players();
+    let final_state = rules::decode_moves(&moves)
+        .and_then(|moves| rules::replay(seats.len(), &game_room.deck_seed, &moves))
+        .map_err(|e| {
+            msg!("Error: Invalid transcript: {}", e);
+            UnoError::InvalidTranscript
+        })?;
+    
+    // Zwycięzcą jest gracz, który w odtworzonej grze pozbył się wszystkich kart
+    let winner_index = match final_state.winner {
+        Some(index) => index,
+        None => {
+            msg!("Error: Transcript does not end with a winner");
+            return Err(UnoError::TranscriptIncomplete.into());
+        }
+    };
+    let winner = seats[winner_index];
+    
+    // Pozostali gracze są klasyfikowani według liczby kart w ręce (remis - kolejność miejsc przy stole)
+    let mut order: Vec<usize> = (0..seats.len()).filter(|index| *index != winner_index).collect();
+    order.sort_by_key(|index| final_state.hands[*index].len());
+    let placements = std::iter::once(winner)
+        .chain(order.into_iter().map(|index| seats[index]))
+        .collect();
+    
+    // Gracze, którzy się poddali, zajmują ostatnie miejsca niezależnie od kart w ręce
+    let placements = game_room.rank_forfeits_last(placements);
+    
+    // Ustawienie zakończenia gry i kolejności miejsc
+    game_room.status = GameStatus::Completed;
+    game_room.set_placements(placements)?;
+    
+    // Zapisanie czasu zakończenia
+    let clock = Clock::get()?;
+    game_room.game_ended_at = Some(clock.unix_timestamp);
+    
+    // Zapisanie zaktualizowanych danych
+    store_room(&game_room, game_account, initiator_account, accounts)?;
+    
+    msg!("Gra UNO rozliczona na podstawie zapisu ruchów. Zwycięzca: {}", winner);
+    Ok(())
+}
+
+/// Implementacja składania zobowiązania do ziarna talii
+fn process_commit_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    commitment: [u8; 32],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let player_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !player_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Zerowe zobowiązanie oznacza jego brak
+    if commitment == [0u8; 32] {
+        msg!("Error: Empty seed commitment");
+        return Err(ProgramError::InvalidArgument);
+    }
+    
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
+    
+    // Ujawnianie ziaren blokuje dołączanie, więc pokój z auto-startem nigdy by się nie zapełnił
+    if game_room.auto_start {
+        msg!("Error: Auto-start rooms do not use seed commitments");
+        return Err(UnoError::AutoStartRoom.into());
+    }
+    
+    let index = game_room.player_index(player_account.key).ok_or_else(|| {
+        msg!("Error: Player is not in this room");
+        UnoError::NotAPlayer
+    })?;
+    
+    // Zobowiązania nie można zmienić - inaczej gracz dopasowałby je do cudzych ziaren
+    if game_room.seed_commitments[index] != [0u8; 32] {
+        msg!("Error: Seed already committed");
+        return Err(UnoError::SeedAlreadyCommitted.into());
+    }
+    
+    game_room.seed_commitments[index] = commitment;
+    game_room.record_activity(player_account.key, Clock::get()?.unix_timestamp);
+    store_room(&game_room, game_account, player_account, accounts)?;
+    
+    msg!("Gracz {} złożył zobowiązanie do ziarna talii", player_account.key);
+    Ok(())
+}
+
+/// Implementacja ujawniania ziarna talii
+fn process_reveal_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: [u8; 32],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let player_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !player_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
+    
+    let index = game_room.player_index(player_account.key).ok_or_else(|| {
+        msg!("Error: Player is not in this room");
+        UnoError::NotAPlayer
+    })?;
+    
+    // Ujawnianie zaczyna się dopiero, gdy wszyscy gracze są zobowiązani
+    if game_room.seed_commitments.contains(&[0u8; 32]) {
+        msg!("Error: Not all players have committed their seeds");
+        return Err(UnoError::SeedsNotCommitted.into());
+    }
+    
+    if game_room.seeds_revealed[index] {
+        msg!("Error: Seed already revealed");
+        return Err(UnoError::SeedAlreadyRevealed.into());
+    }
+    
+    if seed_commitment(player_account.key, &seed) != game_room.seed_commitments[index] {
+        msg!("Error: Revealed seed does not match the commitment");
+        return Err(UnoError::InvalidSeedReveal.into());
+    }
+    
+    for (accumulated, byte) in game_room.seed_accumulator.iter_mut().zip(seed.iter()) {
+        *accumulated ^= byte;
+    }
+    game_room.seeds_revealed[index] = true;
+    game_room.record_activity(player_account.key, Clock::get()?.unix_timestamp);
+    store_room(&game_room, game_account, player_account, accounts)?;
+    
+    msg!("Gracz {} ujawnił ziarno talii", player_account.key);
+    Ok(())
+}
+
+/// Odczytuje najnowszy hash slotu z konta SlotHashes sysvar
+/// (sysvar jest zbyt duży dla `SlotHashes::from_account_info`, więc czytamy pierwszy wpis ręcznie)
+fn read_recent_slot_hash(slot_hashes_account: &AccountInfo) -> Result<[u8; 32], ProgramError> {
+    if *slot_hashes_account.key != solana_program::sysvar::slot_hashes::ID {
+        msg!("Error: Invalid SlotHashes sysvar account: {}", slot_hashes_account.key);
+        return Err(UnoError::InvalidSlotHashes.into());
+    }
+    
+    // Układ: liczba wpisów (u64), potem wpisy (slot u64, hash 32 bajty) od najnowszego
+    let data = slot_hashes_account.data.borrow();
+    if data.len() < 8 + 8 + 32 || data[..8] == [0u8; 8] {
+        msg!("Error: SlotHashes sysvar is empty");
+        return Err(UnoError::InvalidSlotHashes.into());
+    }
+    
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&data[16..48]);
+    Ok(hash)
+}
+
+/// Sprawdza, czy przekazane konto jest System Programem
+fn check_system_program(system_program: &AccountInfo) -> ProgramResult {
+    if *system_program.key != solana_program::system_program::ID {
+        msg!("Error: Invalid system program account: {}", system_program.key);
+        return Err(UnoError::InvalidSystemProgram.into());
+    }
+    Ok(())
+}
+
+/// Tworzy konto PDA podpisane `seeds`. Adresy PDA są przewidywalne, więc ktoś mógł już
+/// przelać na nie lamporty - wtedy `create_account` zawodzi, a zamiast niego dopłacamy
+/// brakujący czynsz i wykonujemy `allocate` oraz `assign` podpisane przez PDA.
+fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    target: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let required = rent.minimum_balance(space);
+    let current = target.lamports();
+    
+    if current == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, target.key, required, space as u64, owner),
+            &[payer.clone(), target.clone(), system_program.clone()],
+            &[seeds],
+        );
+    }
+    
+    msg!("Account {} already holds {} lamports, topping up to rent exemption", target.key, current);
+    if current < required {
+        invoke(
+            &system_instruction::transfer(payer.key, target.key, required - current),
+            &[payer.clone(), target.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(target.key, space as u64),
+        &[target.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(target.key, owner),
+        &[target.clone(), system_program.clone()],
+        &[seeds],
+    )
+}
+
+/// Zapisuje pokój na koncie. Konto pokoju w starszym układzie (`LegacyGameRoom::SIZE`) jest przy
+/// pierwszym zapisie powiększane do bieżącego rozmiaru - brakujący czynsz dopłaca podpisujący,
+/// a wśród kont instrukcji musi być System Program (`instruction::with_room_resize`).
+fn store_room<'a>(
+    game_room: &GameRoom,
+    game_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let space = GameRoom::space_with_access(game_room.max_players, &game_room.access);
+    if game_account.data_len() < space {
+        let system_program = accounts
+            .iter()
+            .find(|account| *account.key == solana_program::system_program::ID)
+            .ok_or_else(|| {
+                msg!("Error: Room account needs {} bytes, resizing requires the System Program", space);
+                ProgramError::AccountDataTooSmall
+            })?;
+        
+        let rent = Rent::get()?;
+        let top_up = rent.minimum_balance(space).saturating_sub(rent.minimum_balance(game_account.data_len()));
+        msg!("Resizing room account from {} to {} bytes, rent top-up: {}", game_account.data_len(), space, top_up);
+        invoke(
+            &system_instruction::transfer(payer.key, game_account.key, top_up),
+            &[payer.clone(), game_account.clone(), system_program.clone()],
+        )?;
+        game_account.realloc(space, false)?;
+    }
+    
+    game_room.to_account_data(&mut game_account.data.borrow_mut())
+}
+
+/// Wczytuje pokój z konta, sprawdzając właściciela, typ konta oraz adres PDA
+/// `[b"uno_game", twórca, nonce lub slot, bump]` - wspólna walidacja dla wszystkich instrukcji
+pub fn load_room(program_id: &Pubkey, game_account: &AccountInfo) -> Result<GameRoom, ProgramError> {
+    if game_account.owner != program_id {
+        msg!("Error: Game account is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    
+    let (mut game_room, version) = GameRoom::from_account_data_with_version(&game_account.data.borrow())?;
+    
+    // Pokoje sprzed wersji 2 nie mają zapisanego bumpa - wyznaczamy go przy odczycie.
+    // Ich konta mają stały rozmiar `LegacyGameRoom::SIZE`, więc bieżący układ się w nich nie
+    // mieści - `store_room` powiększa konto przy pierwszym zapisie.
+    let address_seed = game_room.address_seed();
+    if version < 2 {
+        let (_, bump_seed) = Pubkey::find_program_address(
+            &[ROOM_SEED, game_room.creator.as_ref(), &address_seed],
+            program_id,
+        );
+        game_room.bump = bump_seed;
+    }
+    
+    let expected_game_pubkey = Pubkey::create_program_address(
+        &[ROOM_SEED, game_room.creator.as_ref(), &address_seed, &[game_room.bump]],
+        program_id,
+    )
+    .map_err(|_| UnoError::InvalidRoomAddress)?;
+    
+    if expected_game_pubkey != *game_account.key {
+        msg!("Error: Invalid PDA. Expected: {}, Got: {}", expected_game_pubkey, game_account.key);
+        return Err(UnoError::InvalidRoomAddress.into());
+    }
+    
+    Ok(game_room)
+}
+
+/// Sprawdza, czy wynik gry zatwierdził serwer gry lub wszyscy gracze w pokoju
+fn check_result_approval(
+    game_room: &GameRoom,
+    initiator_account: &AccountInfo,
+    other_signers: &[AccountInfo],
+) -> ProgramResult {
+    if *initiator_account.key == game_room.result_authority {
+        return Ok(());
+    }
+    
+    if !game_room.players.contains(initiator_account.key) {
+        msg!("Error: Initiator is neither the game server nor a player in this room");
+        return Err(UnoError::NotAPlayer.into());
+    }
+    
+    // Bez podpisu serwera każdy gracz musi podpisać ten sam wynik
+    for player in &game_room.players {
+        let signed = *player == *initiator_account.key
+            || other_signers.iter().any(|account| account.key == player && account.is_signer);
+        if !signed {
+            msg!("Error: Missing result approval from player {}", player);
+            return Err(UnoError::ResultNotApproved.into());
+        }
+    }
+    
+    Ok(())
+}
+
+/// Przenosi lamporty z konta należącego do programu, z kontrolą przepełnienia
+fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let from_balance = from.lamports()
+        .checked_sub(amount)
+        .ok_or(UnoError::InsufficientPrizePool)?;
+    let to_balance = to.lamports()
+        .checked_add(amount)
+        .ok_or(UnoError::ArithmeticOverflow)?;
+    
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+    Ok(())
+}
+
+/// Zamyka konto pokoju: zeruje dane i przekazuje pozostałe lamporty (czynsz) na wskazane konto
+fn close_room_account(game_account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let remaining_lamports = game_account.lamports();
+    transfer_lamports(game_account, destination, remaining_lamports)?;
+    game_account.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+/// Sprawdza, czy przekazane konto jest SPL Token programem
+fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+    if *token_program.key != spl_token::ID {
+        msg!("Error: Invalid token program account: {}", token_program.key);
+        return Err(UnoError::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Sprawdza SPL Token program oraz adres PDA skarbca tokenów pokoju
+fn check_room_vault(
+    program_id: &Pubkey,
+    game_room: &GameRoom,
+    game_account: &AccountInfo,
+    vault_account: &AccountInfo,
+    token_program: &AccountInfo,
+) -> ProgramResult {
+    check_token_program(token_program)?;
+    
+    let expected_vault = Pubkey::create_program_address(
+        &[VAULT_SEED, game_account.key.as_ref(), &[game_room.vault_bump]],
+        program_id,
+    )
+    .map_err(|_| UnoError::InvalidVault)?;
+    
+    if expected_vault != *vault_account.key {
+        msg!("Error: Invalid vault PDA. Expected: {}, Got: {}", expected_vault, vault_account.key);
+        return Err(UnoError::InvalidVault.into());
+    }
+    Ok(())
+}
+
+/// Sprawdza, czy konto tokenowe należy do danego portfela i ma właściwy mint
+fn check_token_account(token_account: &AccountInfo, owner: &Pubkey, mint: &Pubkey) -> ProgramResult {
+    if *token_account.owner != spl_token::ID {
+        msg!("Error: Token account {} is not owned by the token program", token_account.key);
+        return Err(UnoError::InvalidTokenAccount.into());
+    }
+    
+    let state = TokenAccount::unpack(&token_account.data.borrow())
+        .map_err(|_| UnoError::InvalidTokenAccount)?;
+    if state.owner != *owner || state.mint != *mint {
+        msg!("Error: Token account {} does not belong to {} for mint {}", token_account.key, owner, mint);
+        return Err(UnoError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+/// Jak `check_token_account`, ale konto innego gracza zgłasza jako niezgodność listy zwrotów
+fn check_refund_token_account(token_account: &AccountInfo, player: &Pubkey, mint: &Pubkey) -> ProgramResult {
+    check_token_account(token_account, player, mint).map_err(|_| {
+        msg!("Error: Refund token account {} does not match player {}", token_account.key, player);
+        UnoError::RefundAccountMismatch.into()
+    })
+}
+
+/// Saldo konta tokenowego
+fn token_balance(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    let state = TokenAccount::unpack(&token_account.data.borrow())
+        .map_err(|_| UnoError::InvalidTokenAccount)?;
+    Ok(state.amount)
+}
+
+/// Przenosi tokeny ze skarbca pokoju - PDA pokoju podpisuje jako właściciel skarbca
+fn transfer_from_vault<'a>(
+    game_room: &GameRoom,
+    game_account: &AccountInfo<'a>,
+    vault_account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            vault_account.key,
+            destination.key,
+            game_account.key,
+            &[],
+            amount,
+        )?,
+        &[
+            vault_account.clone(),
+            destination.clone(),
+            game_account.clone(),
+            token_program.clone(),
+        ],
+        &[&[ROOM_SEED, game_room.creator.as_ref(), &game_room.address_seed(), &[game_room.bump]]],
+    )
+}
+
+/// Zamyka skarbiec tokenów pokoju, przekazując jego czynsz na wskazane konto. SPL Token nie zamknie
+/// niepustego konta, więc resztę tokenów (np. przesłanych na skarbiec spoza gry) odbiera najpierw twórca.
+fn close_vault<'a>(
+    game_room: &GameRoom,
+    game_account: &AccountInfo<'a>,
+    vault_account: &AccountInfo<'a>,
+    creator_token_account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let remainder = token_balance(vault_account)?;
+    if remainder > 0 {
+        msg!("Sweeping {} leftover tokens from the vault to the creator", remainder);
+        transfer_from_vault(game_room, game_account, vault_account, creator_token_account, token_program, remainder)?;
+    }
+    
+    invoke_signed(
+        &spl_token::instruction::close_account(
+            token_program.key,
+            vault_account.key,
+            destination.key,
+            game_account.key,
+            &[],
+        )?,
+        &[
+            vault_account.clone(),
+            destination.clone(),
+            game_account.clone(),
+            token_program.clone(),
+        ],
+        &[&[ROOM_SEED, game_room.creator.as_ref(), &game_room.address_seed(), &[game_room.bump]]],
+    )
+}
+
+/// Implementacja odbierania nagrody - każde zajęte miejsce z tabeli wypłat odbiera swój udział osobno
+fn process_claim_prize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let winner_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let _system_program = next_account_info(accounts_iter)?;
+    let platform_fee_account = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let creator_account = next_account_info(accounts_iter)?;
+    
+    msg!("Claim prize - Claimer account: {}", winner_account.key);
+    msg!("Claim prize - Game account: {}", game_account.key);
+    msg!("Claim prize - Platform fee account: {}", platform_fee_account.key);
+    
+    // Portfel platformy i prowizja pochodzą z globalnej konfiguracji
+    let config = ProgramConfig::load(program_id, config_account)?;
+    
+    // Weryfikacja, że podano prawidłowy adres platformy
+    if *platform_fee_account.key != config.params.fee_recipient {
+        msg!("Error: Invalid platform fee account");
+        return Err(UnoError::InvalidPlatformWallet.into());
+    }
+    
+    // Weryfikacja podpisu
+    if !winner_account.is_signer {
+        msg!("Error: Claimer account is not a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    // Wczytanie danych pokoju (z weryfikacją właściciela i adresu PDA)
+    let mut game_room = load_room(program_id, game_account)?;
+    
+    msg!("Game room loaded. Status: {:?}", game_room.status);
+    msg!("Finishing order: {:?}", game_room.placements);
+    msg!("Shares claimed: {:?}", game_room.payouts_claimed);
+    
+    // Sprawdzenie stanu gry
+    if game_room.status != GameStatus::Completed {
+        msg!("Error: Game is not completed. Current status: {:?}", game_room.status);
+        return Err(UnoError::GameNotCompleted.into());
+    }
+    
+    // Sprawdzenie czy gracz zajął miejsce objęte tabelą wypłat
+    let place = game_room.placements.iter()
+        .take(game_room.payout_bps.len())
+        .position(|player| player == winner_account.key)
+        .ok_or_else(|| {
+            msg!("Error: Claimer {} has no payout share", winner_account.key);
+            UnoError::NotTheWinner
+        })?;
+    
+    // Sprawdzenie czy udział nie został już odebrany
+    if game_room.payouts_claimed[place] {
+        msg!("Error: Prize share for place {} already claimed", place + 1);
+        return Err(UnoError::PrizeAlreadyClaimed.into());
+    }
+    
+    // Czynsz za konto pokoju wraca do twórcy
+    if *creator_account.key != game_room.creator {
+        msg!("Error: Rent recipient {} is not the room creator", creator_account.key);
+        return Err(UnoError::NotRoomCreator.into());
+    }
+    
+    // Obliczenie całkowitej puli i udziału za zajęte miejsce
+    let total_prize = game_room.entry_fee_lamports
+        .checked_mul(game_room.players.len() as u64)
+        .ok_or(UnoError::ArithmeticOverflow)?;
+    let share = game_room.payout_share(place, total_prize)?;
+    msg!("Total prize pool: {}, share for place {}: {}", total_prize, place + 1, share);
+    
+    // Prowizja platformy pobierana proporcjonalnie od każdego udziału
+    let platform_fee = config.platform_fee(share)?;
+    let winner_prize = share - platform_fee;
+    
+    msg!("Platform fee ({} bps): {}", config.params.fee_basis_points, platform_fee);
+    msg!("Player prize: {}", winner_prize);
+    
+    game_room.payouts_claimed[place] = true;
+    game_room.prize_claimed = game_room.payouts_claimed.iter().all(|claimed| *claimed);
+    
+    // Pokój z tokenem SPL wypłaca nagrodę ze skarbca tokenów
+    if let Some(mint) = game_room.mint {
+        let winner_token_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let treasury_token_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        
+        check_room_vault(program_id, &game_room, game_account, vault_account, token_program)?;
+        check_token_account(winner_token_account, winner_account.key, &mint)?;
+        
+        // Prowizja trafia na powiązane konto tokenowe portfela platformy
+        let expected_treasury = spl_associated_token_account::get_associated_token_address(
+            &config.params.fee_recipient,
+            &mint,
+        );
+        if *treasury_token_account.key != expected_treasury {
+            msg!("Error: Invalid treasury token account. Expected: {}", expected_treasury);
+            return Err(UnoError::InvalidPlatformWallet.into());
+        }
+        
+        if token_balance(vault_account)? < share {
+            msg!("Error: Insufficient tokens in vault for prize share {}", share);
+            return Err(UnoError::InsufficientPrizePool.into());
+        }
+        
+        if platform_fee > 0 {
+            transfer_from_vault(&game_room, game_account, vault_account, treasury_token_account, token_program, platform_fee)?;
+        }
+        transfer_from_vault(&game_room, game_account, vault_account, winner_token_account, token_program, winner_prize)?;
+        
+        if game_room.prize_claimed {
+            // Zamknięcie skarbca i pokoju - czynsz obu kont wraca do twórcy. Konto tokenowe twórcy
+            // jest potrzebne tylko tutaj, więc zamknięte konto twórcy nie blokuje wcześniejszych wypłat.
+            let creator_token_account = next_account_info(accounts_iter)?;
+            check_token_account(creator_token_account, &game_room.creator, &mint)?;
+            close_vault(&game_room, game_account, vault_account, creator_token_account, creator_account, token_program)?;
+            close_room_account(game_account, creator_account)?;
+            msg!("Pokój zamknięty");
+        } else {
+            store_room(&game_room, game_account, winner_account, accounts)?;
+        }
+        
+        msg!("Prize share claimed in tokens. Platform fee: {}, Player prize: {}", platform_fee, winner_prize);
+        return Ok(());
+    }
+    
+    // Sprawdź czy konto ma wystarczające środki
+    let rent = Rent::get()?;
+    let rent_exempt_balance = rent.minimum_balance(game_account.data_len());
+    let available_balance = game_account.lamports().saturating_sub(rent_exempt_balance);
+    
+    msg!("Game account balance: {} lamports", game_account.lamports());
+    msg!("Rent exempt balance: {} lamports", rent_exempt_balance);
+    msg!("Available for distribution: {} lamports", available_balance);
+    
+    if available_balance < share {
+        msg!("Error: Insufficient funds in game account. Available: {}, needs: {}", 
+            available_balance, share);
+        return Err(UnoError::InsufficientPrizePool.into());
+    }
+    
+    // Transfer prowizji do portfela platformy
+    if platform_fee > 0 {
+        msg!("Transferring platform fee: {} lamports", platform_fee);
+        transfer_lamports(game_account, platform_fee_account, platform_fee)?;
+    }
+    
+    // Transfer nagrody do gracza
+    msg!("Transferring player prize: {} lamports", winner_prize);
+    transfer_lamports(game_account, winner_account, winner_prize)?;
+    
+    msg!("All transfers completed successfully");
+    
+    if game_room.prize_claimed {
+        // Zamknięcie pokoju po wypłacie wszystkich udziałów - zwrot czynszu twórcy
+        close_room_account(game_account, creator_account)?;
+        msg!("Pokój zamknięty");
+    } else {
+        store_room(&game_room, game_account, winner_account, accounts)?;
+    }
+    
+    msg!("Prize share claimed. Platform fee: {} lamports, Player prize: {} lamports", platform_fee, winner_prize);
+    Ok(())
+}
+
+/// Implementacja anulowania pokoju
+fn process_cancel_room(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let host_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !host_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    check_system_program(system_program)?;
+    
+    // Wczytanie danych pokoju
+    let game_room = load_room(program_id, game_account)?;
+    
+    // Sprawdzenie czy osoba wywołująca jest gospodarzem
+    if game_room.host != *host_account.key {
+        msg!("Error: Only the room host can cancel the room");
+        return Err(UnoError::NotRoomHost.into());
+    }
+    
+    // Sprawdzenie stanu pokoju (można anulować tylko oczekujący pokój)
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
+    
+    // Pokój z tokenem SPL zwraca wpisowe ze skarbca na konta tokenowe graczy
+    if let Some(mint) = game_room.mint {
+        let token_program = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+        check_room_vault(program_id, &game_room, game_account, vault_account, token_program)?;
+        
+        if *creator_account.key != game_room.creator {
+            msg!("Error: Rent recipient {} is not the room creator", creator_account.key);
+            return Err(UnoError::RefundAccountMismatch.into());
+        }
+        
+        let mut creator_token_account = None;
+        for player_pubkey in &game_room.players {
+            let player_token_account = next_account_info(accounts_iter)?;
+            check_refund_token_account(player_token_account, player_pubkey, &mint)?;
+            transfer_from_vault(&game_room, game_account, vault_account, player_token_account, token_program, game_room.entry_fee_lamports)?;
+            
+            if *player_pubkey == game_room.creator {
+                creator_token_account = Some(player_token_account);
+            }
+        }
+        
+        let creator_token_account = creator_token_account.ok_or(UnoError::RefundAccountMismatch)?;
+        close_vault(&game_room, game_account, vault_account, creator_token_account, creator_account, token_program)?;
+        close_room_account(game_account, creator_account)?;
+        
+        msg!("Pokój UNO anulowany. Zwrócono wpisowe w tokenach wszystkim graczom.");
+        return Ok(());
+    }
+    
+    // Zwrot wpisowego każdemu graczowi poza gospodarzem. Konto pokoju należy do programu,
+    // więc System Program nie może go obciążyć - lamporty przenosimy bezpośrednio.
+    // Twórca (zawsze obecny w pokoju) nie dostaje osobnego przelewu - odbiera resztę przy zamknięciu.
+    let mut creator_account = (game_room.host == game_room.creator).then_some(host_account);
+    for player_pubkey in game_room.players.iter().filter(|p| **p != game_room.host) {
+        // Pobierz konto gracza z przekazanych kont
+        let player_account = next_account_info(accounts_iter)?;
+        
+        // Sprawdź, czy konto odpowiada kluczowi publicznemu
+        if *player_account.key != *player_pubkey {
+            msg!("Error: Refund account {} does not match player {}", player_account.key, player_pubkey);
+            return Err(UnoError::RefundAccountMismatch.into());
+        }
+        
+        if *player_pubkey == game_room.creator {
+            creator_account = Some(player_account);
+            continue;
+        }
+        
+        transfer_lamports(game_account, player_account, game_room.entry_fee_lamports)?;
+        msg!("Refunded {} lamports to {}", game_room.entry_fee_lamports, player_pubkey);
+    }
+    
+    // Gospodarz inny niż twórca odbiera swoje wpisowe jak pozostali gracze
+    if game_room.host != game_room.creator {
+        transfer_lamports(game_account, host_account, game_room.entry_fee_lamports)?;
+    }
+    
+    // Resztę środków (wpisowe twórcy i czynsz) odbiera twórca, a konto pokoju jest zamykane
+    let creator_account = creator_account.ok_or(UnoError::RefundAccountMismatch)?;
+    close_room_account(game_account, creator_account)?;
+    
+    msg!("Pokój UNO anulowany. Zwrócono wpisowe wszystkim graczom.");
+    Ok(())
+}
+
+/// Implementacja zwrotu wpisowego z porzuconej gry
+fn process_reclaim_abandoned(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let initiator_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !initiator_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    
+    let config = ProgramConfig::load(program_id, config_account)?;
+    let game_room = load_room(program_id, game_account)?;
+    
+    if !game_room.players.contains(initiator_account.key) {
+        msg!("Error: Initiator is not a player in this room");
+        return Err(UnoError::NotAPlayer.into());
+    }
+    
+    if game_room.status != GameStatus::InProgress {
+        msg!("Error: Game is not in progress");
+        return Err(UnoError::GameNotInProgress.into());
+    }
+    
+    // Sprawdzenie czy minął termin porzucenia gry
+    let started_at = game_room.game_started_at.ok_or(UnoError::GameNotInProgress)?;
+    let deadline = started_at
+        .checked_add(config.params.abandon_timeout_secs)
+        .ok_or(UnoError::ArithmeticOverflow)?;
+    let clock = Clock::get()?;
+    
+    if clock.unix_timestamp < deadline {
+        msg!("Error: Game can be reclaimed after {}, now: {}", deadline, clock.unix_timestamp);
+        return Err(UnoError::GameNotAbandoned.into());
+    }
+    
+    // Pokój z tokenem SPL: pula w skarbcu dzielona po równo, najwyżej do wysokości wpisowego
+    if let Some(mint) = game_room.mint {
+        let token_program = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+        check_room_vault(program_id, &game_room, game_account, vault_account, token_program)?;
+        
+        if *creator_account.key != game_room.creator {
+            msg!("Error: Rent recipient {} is not the room creator", creator_account.key);
+            return Err(UnoError::NotRoomCreator.into());
+        }
+        
+        let refund = game_room.entry_fee_lamports
+            .min(token_balance(vault_account)? / game_room.players.len() as u64);
+        msg!("Refunding {} tokens to each of {} players", refund, game_room.players.len());
+        
+        let mut creator_token_account = None;
+        for player_pubkey in &game_room.players {
+            let player_token_account = next_account_info(accounts_iter)?;
+            check_refund_token_account(player_token_account, player_pubkey, &mint)?;
+            transfer_from_vault(&game_room, game_account, vault_account, player_token_account, token_program, refund)?;
+            
+            if *player_pubkey == game_room.creator {
+                creator_token_account = Some(player_token_account);
+            }
+        }
+        
+        // Ewentualną resztę tokenów odbiera twórca przy zamknięciu skarbca
+        let creator_token_account = creator_token_account.ok_or(UnoError::RefundAccountMismatch)?;
+        close_vault(&game_room, game_account, vault_account, creator_token_account, creator_account, token_program)?;
+        close_room_account(game_account, creator_account)?;
+        
+        msg!("Porzucona gra UNO zamknięta. Zwrócono wpisowe w tokenach wszystkim graczom.");
+        return Ok(());
+    }
     
-    // Inicjalizacja danych pokoju
-    let clock = Clock::get()?;
-    let current_timestamp = clock.unix_timestamp;
+    // Pula do zwrotu (bez czynszu) dzielona po równo, najwyżej do wysokości wpisowego
+    let rent = Rent::get()?;
+    let rent_exempt_balance = rent.minimum_balance(game_account.data_len());
+    let available_balance = game_account.lamports().saturating_sub(rent_exempt_balance);
+    let refund = game_room.entry_fee_lamports
+        .min(available_balance / game_room.players.len() as u64);
     
-    let game_room = GameRoom::new(
-        *creator_account.key,
-        max_players,
-        entry_fee_lamports,
-        current_timestamp,
-        room_slot,
-    );
+    msg!("Refunding {} lamports to each of {} players", refund, game_room.players.len());
     
-    // Serializacja i zapisanie danych
-    game_room.to_account_data(&mut game_account.data.borrow_mut())?;
+    let mut creator_account = None;
+    for player_pubkey in &game_room.players {
+        let player_account = next_account_info(accounts_iter)?;
+        
+        if *player_account.key != *player_pubkey {
+            msg!("Error: Refund account {} does not match player {}", player_account.key, player_pubkey);
+            return Err(UnoError::RefundAccountMismatch.into());
+        }
+        
+        transfer_lamports(game_account, player_account, refund)?;
+        
+        if *player_pubkey == game_room.creator {
+            creator_account = Some(player_account);
+        }
+    }
+    
+    // Czynsz i ewentualna reszta wracają do twórcy, który opłacił konto
+    let creator_account = creator_account.ok_or(UnoError::RefundAccountMismatch)?;
+    close_room_account(game_account, creator_account)?;
     
-    msg!("Utworzono nowy pokój gry UNO w slocie {}", room_slot);
+    msg!("Porzucona gra UNO zamknięta. Zwrócono wpisowe wszystkim graczom.");
     Ok(())
 }
 
-/// Implementacja dołączania do pokoju
-fn process_join_room(
-    _program_id: &Pubkey,
+/// Implementacja opuszczania pokoju przed startem gry
+fn process_leave_room(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     
     let player_account = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    
-    msg!("Join room - Player: {}", player_account.key);
-    msg!("Join room - Game account: {}", game_account.key);
     
     // Weryfikacja podpisu
     if !player_account.is_signer {
-        msg!("Error: Missing required signature");
         return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Sprawdzenie rozmiaru konta przed deserializacją
-    msg!("Game account data length: {}", game_account.data_len());
-    
-    // Wczytanie danych pokoju
-    let mut game_room = GameRoom::from_account_data(&game_account.data.borrow())?;
-    
-    msg!("Game room loaded successfully");
-    msg!("Current players: {:?}", game_room.players);
-    msg!("Entry fee: {}", game_room.entry_fee_lamports);
+    let mut game_room = load_room(program_id, game_account)?;
     
-    // Sprawdzenie stanu pokoju
     if game_room.status != GameStatus::WaitingForPlayers {
         msg!("Error: Room is not waiting for players");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(UnoError::RoomNotWaiting.into());
     }
     
-    // Sprawdzenie czy gracz już jest w pokoju
-    if game_room.players.contains(player_account.key) {
-        msg!("Error: Player already in room");
-        return Err(ProgramError::InvalidArgument);
+    // Twórca nie może wyjść - może jedynie anulować cały pokój
+    if game_room.creator == *player_account.key {
+        msg!("Error: Room creator cannot leave, use CancelRoom instead");
+        return Err(UnoError::CreatorCannotLeave.into());
     }
     
-    // Sprawdzenie czy pokój nie jest już pełny
-    if game_room.players.len() >= game_room.max_players as usize {
-        msg!("Error: Room is full");
-        return Err(ProgramError::InvalidArgument);
+    if game_room.seed_reveal_started() {
+        msg!("Error: Seed reveal already started");
+        return Err(UnoError::SeedRevealInProgress.into());
     }
     
-    msg!("Transferring entry fee: {} lamports", game_room.entry_fee_lamports);
-    
-    // Transfer wpisowego
-    invoke(
-        &system_instruction::transfer(
-            player_account.key,
-            game_account.key,
-            game_room.entry_fee_lamports,
-        ),
-        &[
-            player_account.clone(),
-            game_account.clone(),
-            system_program.clone(),
-        ],
-    )?;
-    
-    msg!("Entry fee transferred successfully");
-    
-    // Dodanie gracza do listy
-    game_room.players.push(*player_account.key);
+    if !game_room.remove_player(player_account.key) {
+        msg!("Error: Player is not in this room");
+        return Err(UnoError::NotAPlayer.into());
+    }
     
-    msg!("Player added to room. Total players: {}", game_room.players.len());
+    // Zwrot wpisowego bezpośrednio z konta pokoju lub ze skarbca tokenów
+    match game_room.mint {
+        None => transfer_lamports(game_account, player_account, game_room.entry_fee_lamports)?,
+        Some(mint) => {
+            let token_program = next_account_info(accounts_iter)?;
+            let vault_account = next_account_info(accounts_iter)?;
+            let player_token_account = next_account_info(accounts_iter)?;
+            check_room_vault(program_id, &game_room, game_account, vault_account, token_program)?;
+            check_refund_token_account(player_token_account, player_account.key, &mint)?;
+            transfer_from_vault(&game_room, game_account, vault_account, player_token_account, token_program, game_room.entry_fee_lamports)?;
+        }
+    }
     
-    // Zapisanie zaktualizowanych danych
-    game_room.to_account_data(&mut game_account.data.borrow_mut())?;
+    store_room(&game_room, game_account, player_account, accounts)?;
     
-    msg!("Dołączono do pokoju gry UNO");
+    msg!("Gracz {} opuścił pokój. Pozostało graczy: {}", player_account.key, game_room.players.len());
     Ok(())
 }
 
-/// Implementacja rozpoczęcia gry
-fn process_start_game(
-    _program_id: &Pubkey,
+/// Implementacja wyrzucania gracza z oczekującego pokoju
+fn process_kick_player(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    game_id: String,
+    player: Pubkey,
+    ban: bool,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     
-    let initiator_account = next_account_info(accounts_iter)?;
+    let host_account = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let player_account = next_account_info(accounts_iter)?;
     
     // Weryfikacja podpisu
-    if !initiator_account.is_signer {
+    if !host_account.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Wczytanie danych pokoju
-    let mut game_room = GameRoom::from_account_data(&game_account.data.borrow())?;
+    let mut game_room = load_room(program_id, game_account)?;
     
-    // Sprawdzenie czy osoba inicjująca jest w pokoju
-    if !game_room.players.contains(initiator_account.key) {
-        return Err(ProgramError::InvalidArgument);
+    if game_room.host != *host_account.key {
+        msg!("Error: Only the room host can kick players");
+        return Err(UnoError::NotRoomHost.into());
     }
     
-    // Sprawdzenie stanu pokoju
     if game_room.status != GameStatus::WaitingForPlayers {
-        return Err(ProgramError::InvalidAccountData);
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
     }
     
-    // Sprawdzenie minimalnej liczby graczy
-    if game_room.players.len() < 2 {
-        return Err(ProgramError::InvalidArgument);
+    // Twórca opłacił konto pokoju i zostaje w nim do końca
+    if player == game_room.creator {
+        msg!("Error: Room creator cannot be kicked, use CancelRoom instead");
+        return Err(UnoError::CreatorCannotLeave.into());
     }
     
-    // Ustawienie statusu gry i zapisanie ID gry off-chain
-    game_room.status = GameStatus::InProgress;
-    game_room.set_game_id(&game_id);
+    // Skład jest zamrożony od pierwszego ujawnienia ziarna (jak przy `LeaveRoom`)
+    if game_room.seed_reveal_started() {
+        msg!("Error: Seed reveal already started");
+        return Err(UnoError::SeedRevealInProgress.into());
+    }
     
-    // Zapisanie czasu rozpoczęcia
-    let clock = Clock::get()?;
-    game_room.game_started_at = Some(clock.unix_timestamp);
+    if *player_account.key != player {
+        msg!("Error: Refund account {} does not match kicked player {}", player_account.key, player);
+        return Err(UnoError::RefundAccountMismatch.into());
+    }
     
-    // Zapisanie zaktualizowanych danych
-    game_room.to_account_data(&mut game_account.data.borrow_mut())?;
+    if !game_room.remove_player(&player) {
+        msg!("Error: Player is not in this room");
+        return Err(UnoError::NotAPlayer.into());
+    }
     
-    msg!("Gra UNO rozpoczęta. Off-chain ID: {}", game_room.get_game_id());
+    if ban && !game_room.banned.contains(&player) {
+        if game_room.banned.len() >= MAX_BANNED_PLAYERS {
+            msg!("Error: Ban list is full");
+            return Err(UnoError::BanListFull.into());
+        }
+        game_room.banned.push(player);
+    }
+    
+    // Zwrot wpisowego bezpośrednio z konta pokoju lub ze skarbca tokenów
+    match game_room.mint {
+        None => transfer_lamports(game_account, player_account, game_room.entry_fee_lamports)?,
+        Some(mint) => {
+            let token_program = next_account_info(accounts_iter)?;
+            let vault_account = next_account_info(accounts_iter)?;
+            let player_token_account = next_account_info(accounts_iter)?;
+            check_room_vault(program_id, &game_room, game_account, vault_account, token_program)?;
+            check_refund_token_account(player_token_account, &player, &mint)?;
+            transfer_from_vault(&game_room, game_account, vault_account, player_token_account, token_program, game_room.entry_fee_lamports)?;
+        }
+    }
+    
+    game_room.record_activity(host_account.key, Clock::get()?.unix_timestamp);
+    store_room(&game_room, game_account, host_account, accounts)?;
+    
+    msg!("Gracz {} wyrzucony z pokoju (ban: {}). Pozostało graczy: {}", player, ban, game_room.players.len());
     Ok(())
 }
 
-/// Implementacja zakończenia gry
-fn process_end_game(
-    _program_id: &Pubkey,
+/// Implementacja przekazania roli gospodarza
+fn process_transfer_host(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    winner: Pubkey,
+    new_host: Pubkey,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     
-    let initiator_account = next_account_info(accounts_iter)?;
+    let signer_account = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
     
     // Weryfikacja podpisu
-    if !initiator_account.is_signer {
+    if !signer_account.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Wczytanie danych pokoju
-    let mut game_room = GameRoom::from_account_data(&game_account.data.borrow())?;
-    
-    // Sprawdzenie czy osoba inicjująca jest w pokoju lub jest twórcą
-    let is_player = game_room.players.contains(initiator_account.key);
-    let is_creator = game_room.creator == *initiator_account.key;
+    let config = ProgramConfig::load(program_id, config_account)?;
+    let mut game_room = load_room(program_id, game_account)?;
     
-    if !is_player && !is_creator {
-        return Err(ProgramError::InvalidArgument);
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
     }
     
-    // Sprawdzenie stanu pokoju
-    if game_room.status != GameStatus::InProgress {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let now = Clock::get()?.unix_timestamp;
     
-    // Sprawdzenie czy zwycięzca jest jednym z graczy
-    if !game_room.players.contains(&winner) {
-        return Err(ProgramError::InvalidArgument);
+    // Gracz może przejąć rolę dopiero, gdy gospodarz nie podpisał niczego przez `host_inactivity_secs`
+    if game_room.host != *signer_account.key {
+        if !game_room.players.contains(signer_account.key) {
+            msg!("Error: Only the room host or a seated player can transfer the host role");
+            return Err(UnoError::NotRoomHost.into());
+        }
+        
+        let inactive_until = game_room.host_active_at
+            .checked_add(config.params.host_inactivity_secs)
+            .ok_or(UnoError::ArithmeticOverflow)?;
+        if now < inactive_until {
+            msg!("Error: Host is active, the role can be taken over at {}", inactive_until);
+            return Err(UnoError::HostStillActive.into());
+        }
     }
     
-    // Ustawienie zakończenia gry i zwycięzcy
-    game_room.status = GameStatus::Completed;
-    game_room.winner = Some(winner);
-    
-    // Zapisanie czasu zakończenia
-    let clock = Clock::get()?;
-    game_room.game_ended_at = Some(clock.unix_timestamp);
+    if !game_room.players.contains(&new_host) {
+        msg!("Error: New host {} is not a player in this room", new_host);
+        return Err(UnoError::NotAPlayer.into());
+    }
     
-    // Zapisanie zaktualizowanych danych
-    game_room.to_account_data(&mut game_account.data.borrow_mut())?;
+    game_room.host = new_host;
+    game_room.host_active_at = now;
+    store_room(&game_room, game_account, signer_account, accounts)?;
     
-    msg!("Gra UNO zakończona. Zwycięzca: {}", winner);
+    msg!("Gospodarzem pokoju jest teraz {}", new_host);
     Ok(())
 }
 
-/// Implementacja odbierania nagrody
-fn process_claim_prize(
+/// Implementacja poddania gry
+fn process_forfeit(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     
-    let winner_account = next_account_info(accounts_iter)?;
+    let player_account = next_account_info(accounts_iter)?;
     let game_account = next_account_info(accounts_iter)?;
-    let _system_program = next_account_info(accounts_iter)?;
-    let platform_fee_account = next_account_info(accounts_iter)?; // NOWE: Konto dla prowizji platformy
     
-    msg!("Claim prize - Winner account: {}", winner_account.key);
-    msg!("Claim prize - Game account: {}", game_account.key);
-    msg!("Claim prize - Platform fee account: {}", platform_fee_account.key);
+    // Weryfikacja podpisu
+    if !player_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
     
-    // STAŁY ADRES PORTFELA PLATFORMY - ZMIEŃ NA SWÓJ!
-    const PLATFORM_WALLET: &str = "FEEfBE29dqRgC8qMv6f9YXTSNbX7LMN3Reo3UsYdoUd8";
-    let platform_pubkey = Pubkey::from_str(PLATFORM_WALLET).unwrap_or_default();
+    let mut game_room = load_room(program_id, game_account)?;
     
-    // Weryfikacja, że podano prawidłowy adres platformy
-    if *platform_fee_account.key != platform_pubkey {
-        msg!("Error: Invalid platform fee account");
-        return Err(ProgramError::InvalidArgument);
+    if game_room.status != GameStatus::InProgress {
+        msg!("Error: Game is not in progress");
+        return Err(UnoError::GameNotInProgress.into());
     }
     
-    // Weryfikacja podpisu
-    if !winner_account.is_signer {
-        msg!("Error: Winner account is not a signer");
-        return Err(ProgramError::MissingRequiredSignature);
+    if !game_room.players.contains(player_account.key) {
+        msg!("Error: Player is not in this room");
+        return Err(UnoError::NotAPlayer.into());
     }
     
-    // Sprawdź czy game_account należy do naszego programu
-    if game_account.owner != program_id {
-        msg!("Error: Game account is not owned by this program");
-        return Err(ProgramError::IncorrectProgramId);
+    if game_room.forfeited.contains(player_account.key) {
+        msg!("Error: Player has already forfeited the game");
+        return Err(UnoError::PlayerForfeited.into());
     }
     
-    // Wczytanie danych pokoju
-    let mut game_room = GameRoom::from_account_data(&game_account.data.borrow())?;
+    game_room.forfeited.push(*player_account.key);
+    msg!("Gracz {} poddał grę", player_account.key);
+    
+    // Ostatni gracz w grze wygrywa, a poddani zajmują kolejne miejsca od końca
+    let remaining = game_room.remaining_players();
+    if let [winner] = remaining[..] {
+        let placements = game_room.rank_forfeits_last(vec![winner]);
+        game_room.status = GameStatus::Completed;
+        game_room.set_placements(placements)?;
+        game_room.game_ended_at = Some(Clock::get()?.unix_timestamp);
+        msg!("Gra UNO zakończona po poddaniu. Zwycięzca: {}", winner);
+    }
     
-    msg!("Game room loaded. Status: {:?}", game_room.status);
-    msg!("Winner in game room: {:?}", game_room.winner);
-    msg!("Prize already claimed: {}", game_room.prize_claimed);
+    store_room(&game_room, game_account, player_account, accounts)?;
+    Ok(())
+}
+
+/// Implementacja zgłaszania gotowości gracza
+fn process_set_ready(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ready: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
     
-    // Sprawdzenie stanu gry
-    if game_room.status != GameStatus::Completed {
-        msg!("Error: Game is not completed. Current status: {:?}", game_room.status);
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let player_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
     
-    // Sprawdzenie czy gracz jest zwycięzcą
-    if game_room.winner != Some(*winner_account.key) {
-        msg!("Error: Claimer is not the winner. Winner: {:?}, Claimer: {}", 
-            game_room.winner, winner_account.key);
-        return Err(ProgramError::InvalidArgument);
+    // Weryfikacja podpisu
+    if !player_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Sprawdzenie czy nagroda nie została już odebrana
-    if game_room.prize_claimed {
-        msg!("Error: Prize already claimed");
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let mut game_room = load_room(program_id, game_account)?;
     
-    // Obliczenie całkowitej puli
-    let total_prize = game_room.entry_fee_lamports * game_room.players.len() as u64;
-    msg!("Total prize pool: {} lamports", total_prize);
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
     
-    // Obliczenie prowizji platformy (5%)
-    let platform_fee = total_prize * 5 / 100; // 5% prowizji
-    let winner_prize = total_prize - platform_fee;
+    let index = game_room.player_index(player_account.key).ok_or_else(|| {
+        msg!("Error: Player is not in this room");
+        UnoError::NotAPlayer
+    })?;
     
-    msg!("Platform fee (5%): {} lamports", platform_fee);
-    msg!("Winner prize (95%): {} lamports", winner_prize);
+    game_room.ready[index] = ready;
+    game_room.record_activity(player_account.key, Clock::get()?.unix_timestamp);
+    store_room(&game_room, game_account, player_account, accounts)?;
     
-    // Sprawdź czy konto ma wystarczające środki
-    let rent = Rent::get()?;
-    let rent_exempt_balance = rent.minimum_balance(game_account.data_len());
-    let available_balance = game_account.lamports().saturating_sub(rent_exempt_balance);
+    msg!("Gracz {} gotowy: {}", player_account.key, ready);
+    Ok(())
+}
+
+/// Implementacja ustawiania kworum gotowości
+fn process_set_ready_quorum(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    quorum: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
     
-    msg!("Game account balance: {} lamports", game_account.lamports());
-    msg!("Rent exempt balance: {} lamports", rent_exempt_balance);
-    msg!("Available for distribution: {} lamports", available_balance);
+    let host_account = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
     
-    if available_balance < total_prize {
-        msg!("Error: Insufficient funds in game account. Available: {}, needs: {}", 
-            available_balance, total_prize);
-        return Err(ProgramError::InsufficientFunds);
+    // Weryfikacja podpisu
+    if !host_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Transfer prowizji do portfela platformy
-    if platform_fee > 0 {
-        msg!("Transferring platform fee: {} lamports", platform_fee);
-        **game_account.try_borrow_mut_lamports()? = game_account.lamports().saturating_sub(platform_fee);
-        **platform_fee_account.try_borrow_mut_lamports()? = platform_fee_account.lamports().saturating_add(platform_fee);
-    }
+    let mut game_room = load_room(program_id, game_account)?;
     
-    // Transfer nagrody do zwycięzcy
-    msg!("Transferring winner prize: {} lamports", winner_prize);
-    **game_account.try_borrow_mut_lamports()? = game_account.lamports().saturating_sub(winner_prize);
-    **winner_account.try_borrow_mut_lamports()? = winner_account.lamports().saturating_add(winner_prize);
+    if game_room.host != *host_account.key {
+        msg!("Error: Only the room host can set the ready quorum");
+        return Err(UnoError::NotRoomHost.into());
+    }
     
-    msg!("All transfers completed successfully");
+    if game_room.status != GameStatus::WaitingForPlayers {
+        msg!("Error: Room is not waiting for players");
+        return Err(UnoError::RoomNotWaiting.into());
+    }
     
-    // Oznaczenie nagrody jako odebranej
-    game_room.prize_claimed = true;
+    // Gra wymaga co najmniej dwóch graczy, a kworum nie może przekraczać pojemności pokoju
+    if quorum != 0 && !(2..=game_room.max_players).contains(&quorum) {
+        msg!("Error: Invalid ready quorum {} for {} seats", quorum, game_room.max_players);
+        return Err(UnoError::InvalidReadyQuorum.into());
+    }
     
-    // Zapisanie zaktualizowanych danych
-    game_room.to_account_data(&mut game_account.data.borrow_mut())?;
+    game_room.ready_quorum = quorum;
+    game_room.record_activity(host_account.key, Clock::get()?.unix_timestamp);
+    store_room(&game_room, game_account, host_account, accounts)?;
     
-    msg!("Prize claimed. Platform fee: {} lamports, Winner prize: {} lamports", platform_fee, winner_prize);
+    msg!("Kworum gotowości ustawione na {}", quorum);
     Ok(())
 }
 
-/// Implementacja anulowania pokoju
-fn process_cancel_room(
+/// Implementacja tworzenia konfiguracji programu
+fn process_initialize_config(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    params: ConfigParams,
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
     
-    let creator_account = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
+    let admin_account = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
+    let program_data_account = next_account_info(accounts_iter)?;
+    
+    check_system_program(system_program)?;
     
     // Weryfikacja podpisu
-    if !creator_account.is_signer {
+    if !admin_account.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
     
-    // Wczytanie danych pokoju
-    let game_room = GameRoom::from_account_data(&game_account.data.borrow())?;
+    // Pierwszym administratorem może zostać tylko upgrade authority programu
+    check_upgrade_authority(program_id, program_data_account, admin_account.key)?;
     
-    // Sprawdzenie czy osoba wywołująca jest twórcą
-    if game_room.creator != *creator_account.key {
-        return Err(ProgramError::InvalidArgument);
+    let (expected_config_pubkey, bump_seed) = Pubkey::find_program_address(&[CONFIG_SEED], program_id);
+    if expected_config_pubkey != *config_account.key {
+        msg!("Error: Invalid config PDA. Expected: {}, Got: {}", expected_config_pubkey, config_account.key);
+        return Err(UnoError::InvalidConfigAccount.into());
     }
     
-    // Sprawdzenie stanu pokoju (można anulować tylko oczekujący pokój)
-    if game_room.status != GameStatus::WaitingForPlayers {
-        return Err(ProgramError::InvalidAccountData);
+    // Konfiguracja może zostać utworzona tylko raz
+    if config_account.owner == program_id {
+        msg!("Error: Config already initialized");
+        return Err(UnoError::ConfigAlreadyInitialized.into());
     }
     
-    // Przygotowanie sygnatury PDA
-    let seeds = &[b"uno_game", game_room.creator.as_ref(), &[game_room.room_slot]];
-    let (_, bump_seed) = Pubkey::find_program_address(seeds, program_id);
-    let signer_seeds = &[b"uno_game", game_room.creator.as_ref(), &[game_room.room_slot], &[bump_seed]];
+    params.validate()?;
     
-    // Zwrot wpisowego każdemu graczowi
-    let mut remaining_accounts_iter = accounts_iter.clone();
-    for player_pubkey in &game_room.players {
-        // Sprawdź, czy to nie jest twórca (już ma swoje konto)
-        if *player_pubkey != game_room.creator {
-            // Pobierz konto gracza z przekazanych kont
-            let player_account = next_account_info(&mut remaining_accounts_iter)?;
-            
-            // Sprawdź, czy konto odpowiada kluczowi publicznemu
-            if *player_account.key != *player_pubkey {
-                return Err(ProgramError::InvalidArgument);
-            }
-            
-            // Zwróć wpisowe
-            invoke_signed(
-                &system_instruction::transfer(
-                    game_account.key,
-                    player_account.key,
-                    game_room.entry_fee_lamports,
-                ),
-                &[
-                    game_account.clone(),
-                    player_account.clone(),
-                    system_program.clone(),
-                ],
-                &[signer_seeds],
-            )?;
+    create_pda_account(
+        admin_account,
+        config_account,
+        system_program,
+        &Rent::get()?,
+        ProgramConfig::SIZE,
+        program_id,
+        &[CONFIG_SEED, &[bump_seed]],
+    )?;
+    
+    let config = ProgramConfig {
+        admin: *admin_account.key,
+        params,
+        bump: bump_seed,
+    };
+    config.to_account_data(&mut config_account.data.borrow_mut())?;
+    
+    msg!("Utworzono konfigurację programu. Administrator: {}", admin_account.key);
+    Ok(())
+}
+
+/// Sprawdza, czy `authority` jest upgrade authority programu zapisanym w jego koncie ProgramData
+fn check_upgrade_authority(program_id: &Pubkey, program_data_account: &AccountInfo, authority: &Pubkey) -> ProgramResult {
+    let (expected_program_data, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
+    if *program_data_account.key != expected_program_data || *program_data_account.owner != bpf_loader_upgradeable::ID {
+        msg!("Error: Invalid program data account: {}", program_data_account.key);
+        return Err(UnoError::InvalidProgramData.into());
+    }
+    
+    let metadata_size = UpgradeableLoaderState::size_of_programdata_metadata();
+    let data = program_data_account.data.borrow();
+    let state: UpgradeableLoaderState = limited_deserialize(&data[..metadata_size.min(data.len())], metadata_size as u64)
+        .map_err(|_| UnoError::InvalidProgramData)?;
+    
+    match state {
+        UpgradeableLoaderState::ProgramData { upgrade_authority_address: Some(upgrade_authority), .. }
+            if upgrade_authority == *authority => Ok(()),
+        UpgradeableLoaderState::ProgramData { .. } => {
+            msg!("Error: {} is not the program upgrade authority", authority);
+            Err(UnoError::NotUpgradeAuthority.into())
+        }
+        _ => {
+            msg!("Error: Program data account holds no program data");
+            Err(UnoError::InvalidProgramData.into())
         }
     }
+}
+
+/// Implementacja aktualizacji konfiguracji programu
+fn process_update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Pubkey,
+    params: ConfigParams,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    
+    let admin_account = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    
+    // Weryfikacja podpisu
+    if !admin_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
     
-    // Zwróć resztę środków (w tym wpisowe twórcy) do twórcy
-    let remaining_lamports = game_account.lamports();
-    **game_account.lamports.borrow_mut() = 0;
-    **creator_account.lamports.borrow_mut() += remaining_lamports;
+    let mut config = ProgramConfig::load(program_id, config_account)?;
     
-    msg!("Pokój UNO anulowany. Zwrócono wpisowe wszystkim graczom.");
+    if config.admin != *admin_account.key {
+        msg!("Error: Only the config admin can update the config");
+        return Err(UnoError::NotConfigAdmin.into());
+    }
+    
+    params.validate()?;
+    
+    config.admin = new_admin;
+    config.params = params;
+    config.to_account_data(&mut config_account.data.borrow_mut())?;
+    
+    msg!("Zaktualizowano konfigurację programu. Administrator: {}", new_admin);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,405 @@
+// Testy wspólnej walidacji kont: właściciel i adres PDA pokoju, System Program
+
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::*;
+use solana_program::{instruction::InstructionError, pubkey::Pubkey, rent::Rent};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use uno_solana::{
+    error::UnoError, instruction, pack_account_data, unpack_account_data, GameRoom, GameRoomV1, GameRoomV2, GameStatus,
+    LegacyGameRoom, ProgramConfig, ProgramConfigV1,
+};
+
+fn waiting_room(creator: Pubkey, room_nonce: u64) -> GameRoom {
+    let (_, bump) = room_pda_and_bump(&creator, room_nonce);
+    GameRoom::new(creator, 2, ENTRY_FEE, 0, room_nonce, Pubkey::new_unique(), bump)
+}
+
+fn in_progress_room(creator: Pubkey, player: Pubkey, room_nonce: u64) -> GameRoom {
+    let mut room = waiting_room(creator, room_nonce);
+    room.players.push(player);
+    room.status = GameStatus::InProgress;
+    room.game_started_at = Some(0);
+    room
+}
+
+#[tokio::test]
+async fn join_rejects_room_owned_by_another_program() {
+    let creator = Pubkey::new_unique();
+    let room_address = room_pda(&creator, 0);
+    let mut program_test = program_test();
+    program_test.add_account(room_address, room_account(&waiting_room(creator, 0), Pubkey::new_unique()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let player = env.funded_keypair(1_000_000_000).await;
+    let result = env.process(&[join_room_ix(&player.pubkey(), &room_address)], &[&player]).await;
+
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
+
+#[tokio::test]
+async fn join_rejects_spoofed_room_address() {
+    let creator = Pubkey::new_unique();
+    let spoofed_address = Pubkey::new_unique();
+    let mut program_test = program_test();
+    program_test.add_account(spoofed_address, room_account(&waiting_room(creator, 0), program_id()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let player = env.funded_keypair(1_000_000_000).await;
+    let result = env.process(&[join_room_ix(&player.pubkey(), &spoofed_address)], &[&player]).await;
+
+    assert_uno_error(result, UnoError::InvalidRoomAddress);
+}
+
+#[tokio::test]
+async fn join_rejects_fake_system_program() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(1_000_000_000).await;
+    env.process(&[create_room_ix(&creator.pubkey(), 2, ENTRY_FEE, 0)], &[&creator]).await.unwrap();
+
+    let player = env.funded_keypair(1_000_000_000).await;
+    let mut ix = join_room_ix(&player.pubkey(), &room_pda(&creator.pubkey(), 0));
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&player]).await;
+
+    assert_uno_error(result, UnoError::InvalidSystemProgram);
+}
+
+#[tokio::test]
+async fn join_accepts_genuine_room() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(1_000_000_000).await;
+    env.process(&[create_room_ix(&creator.pubkey(), 2, ENTRY_FEE, 0)], &[&creator]).await.unwrap();
+
+    let room_address = room_pda(&creator.pubkey(), 0);
+    let player = env.funded_keypair(1_000_000_000).await;
+    env.process(&[join_room_ix(&player.pubkey(), &room_address)], &[&player]).await.unwrap();
+
+    let room = env.room(&room_address).await;
+    assert_eq!(room.players, vec![creator.pubkey(), player.pubkey()]);
+}
+
+#[tokio::test]
+async fn start_game_rejects_spoofed_room_address() {
+    let creator = Keypair::new();
+    let player = Pubkey::new_unique();
+    let spoofed_address = Pubkey::new_unique();
+    let mut room = waiting_room(creator.pubkey(), 0);
+    room.players.push(player);
+    let mut program_test = program_test();
+    program_test.add_account(spoofed_address, room_account(&room, program_id()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let result = env.process(&[start_game_ix(&creator.pubkey(), &spoofed_address, "game")], &[&creator]).await;
+
+    assert_uno_error(result, UnoError::InvalidRoomAddress);
+}
+
+#[tokio::test]
+async fn end_game_rejects_foreign_owned_room() {
+    let creator = Pubkey::new_unique();
+    let player = Pubkey::new_unique();
+    let room_address = room_pda(&creator, 1);
+    let mut program_test = program_test();
+    program_test.add_account(room_address, room_account(&in_progress_room(creator, player, 1), Pubkey::new_unique()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = env.result_authority.insecure_clone();
+    let result = env.process(&[end_game_ix(&authority.pubkey(), &room_address, player)], &[&authority]).await;
+
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
+
+#[tokio::test]
+async fn end_game_rejects_room_seeded_for_another_nonce() {
+    let creator = Pubkey::new_unique();
+    let player = Pubkey::new_unique();
+    // Dane pokoju deklarują nonce 2, ale konto leży pod adresem PDA nonce 3
+    let room_address = room_pda(&creator, 3);
+    let mut program_test = program_test();
+    program_test.add_account(room_address, room_account(&in_progress_room(creator, player, 2), program_id()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let authority = env.result_authority.insecure_clone();
+    let result = env.process(&[end_game_ix(&authority.pubkey(), &room_address, player)], &[&authority]).await;
+
+    assert_uno_error(result, UnoError::InvalidRoomAddress);
+}
+
+#[tokio::test]
+async fn cancel_rejects_spoofed_room_and_fake_system_program() {
+    let creator = Keypair::new();
+    let spoofed_address = Pubkey::new_unique();
+    let mut program_test = program_test();
+    program_test.add_account(spoofed_address, room_account(&waiting_room(creator.pubkey(), 0), program_id()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let result = env.process(&[cancel_room_ix(&creator.pubkey(), &spoofed_address, &[])], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidRoomAddress);
+
+    let mut ix = cancel_room_ix(&creator.pubkey(), &spoofed_address, &[]);
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidSystemProgram);
+}
+
+fn legacy_room(creator: Pubkey, players: Vec<Pubkey>, room_slot: u8) -> LegacyGameRoom {
+    LegacyGameRoom {
+        creator,
+        max_players: 4,
+        entry_fee_lamports: ENTRY_FEE,
+        players,
+        status: GameStatus::WaitingForPlayers,
+        winner: None,
+        created_at: 0,
+        game_started_at: None,
+        game_ended_at: None,
+        prize_claimed: false,
+        game_id: [0u8; 64],
+        room_slot,
+    }
+}
+
+fn legacy_rent() -> u64 {
+    Rent::default().minimum_balance(LegacyGameRoom::SIZE)
+}
+
+#[tokio::test]
+async fn legacy_waiting_room_is_cancelled_with_refunds() {
+    let creator = Keypair::new();
+    let player = Keypair::new();
+    let (room_address, _) = instruction::legacy_room_address(&program_id(), &creator.pubkey(), 4);
+    let room = legacy_room(creator.pubkey(), vec![creator.pubkey(), player.pubkey()], 4);
+    let mut program_test = program_test();
+    program_test.add_account(room_address, legacy_room_account(&room, legacy_rent() + 2 * ENTRY_FEE));
+    let mut env = TestEnv::start(program_test).await;
+
+    // Wpisowe z pokoju zapisanego przez pierwszą wersję programu wraca do graczy
+    let keys = [creator.pubkey(), player.pubkey()];
+    let creator_before = env.lamports(&creator.pubkey()).await;
+    env.process(&[cancel_room_ix(&creator.pubkey(), &room_address, &keys)], &[&creator]).await.unwrap();
+
+    assert_eq!(env.lamports(&player.pubkey()).await, ENTRY_FEE);
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_before + ENTRY_FEE + legacy_rent());
+    assert!(env.account(&room_address).await.is_none());
+}
+
+#[tokio::test]
+async fn legacy_completed_room_pays_out_winner() {
+    let creator = Keypair::new();
+    let winner = Keypair::new();
+    let (room_address, _) = instruction::legacy_room_address(&program_id(), &creator.pubkey(), 0);
+    let mut room = legacy_room(creator.pubkey(), vec![creator.pubkey(), winner.pubkey()], 0);
+    room.status = GameStatus::Completed;
+    room.winner = Some(winner.pubkey());
+    let mut program_test = program_test();
+    program_test.add_account(room_address, legacy_room_account(&room, legacy_rent() + 2 * ENTRY_FEE));
+    let mut env = TestEnv::start(program_test).await;
+    let fee_recipient = env.fee_recipient;
+
+    let ix = claim_prize_ix(&winner.pubkey(), &room_address, &fee_recipient, &creator.pubkey());
+    env.process(&[ix], &[&winner]).await.unwrap();
+
+    let platform_fee = 2 * ENTRY_FEE * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(env.lamports(&winner.pubkey()).await, 2 * ENTRY_FEE - platform_fee);
+    assert_eq!(env.lamports(&creator.pubkey()).await, legacy_rent());
+    assert!(env.account(&room_address).await.is_none());
+}
+
+fn v1_room(creator: Pubkey, players: Vec<Pubkey>, room_slot: u8) -> GameRoomV1 {
+    GameRoomV1 {
+        creator,
+        max_players: 4,
+        entry_fee_lamports: ENTRY_FEE,
+        players,
+        status: GameStatus::WaitingForPlayers,
+        winner: None,
+        created_at: 0,
+        game_started_at: None,
+        game_ended_at: None,
+        prize_claimed: false,
+        game_id: [0u8; 64],
+        room_slot,
+        result_authority: Pubkey::new_unique(),
+    }
+}
+
+#[tokio::test]
+async fn legacy_room_is_resized_on_join_and_completes_a_game() {
+    let creator = Keypair::new();
+    let (room_address, _) = instruction::legacy_room_address(&program_id(), &creator.pubkey(), 1);
+    let room = legacy_room(creator.pubkey(), vec![creator.pubkey()], 1);
+    let mut program_test = program_test();
+    program_test.add_account(room_address, legacy_room_account(&room, legacy_rent() + ENTRY_FEE));
+    let mut env = TestEnv::start(program_test).await;
+    let fee_recipient = env.fee_recipient;
+
+    // Dołączający gracz dopłaca czynsz za powiększenie konta do bieżącego układu
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&player.pubkey(), &room_address)], &[&player]).await.unwrap();
+    let room_rent = Rent::default().minimum_balance(GameRoom::space(4));
+    assert_eq!(env.account(&room_address).await.unwrap().data.len(), GameRoom::space(4));
+    assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE - ENTRY_FEE - (room_rent - legacy_rent()));
+
+    env.prepare_start(&room_address, &[&creator, &player]).await;
+    env.process(&[start_game_ix(&creator.pubkey(), &room_address, "legacy")], &[&creator]).await.unwrap();
+
+    // Pierwsza wersja nie miała serwera wyników - wynik zatwierdzają wszyscy gracze
+    let ix = instruction::end_game_unanimous(&program_id(), &creator.pubkey(), &room_address, &player.pubkey(), &[player.pubkey()]);
+    env.process(&[ix], &[&creator, &player]).await.unwrap();
+
+    let player_before = env.lamports(&player.pubkey()).await;
+    let ix = claim_prize_ix(&player.pubkey(), &room_address, &fee_recipient, &creator.pubkey());
+    env.process(&[ix], &[&player]).await.unwrap();
+
+    let platform_fee = 2 * ENTRY_FEE * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(env.lamports(&player.pubkey()).await, player_before + 2 * ENTRY_FEE - platform_fee);
+    assert_eq!(env.lamports(&creator.pubkey()).await, room_rent);
+    assert!(env.account(&room_address).await.is_none());
+}
+
+#[tokio::test]
+async fn legacy_room_resize_requires_system_program() {
+    let creator = Keypair::new();
+    let player = Keypair::new();
+    let (room_address, _) = instruction::legacy_room_address(&program_id(), &creator.pubkey(), 0);
+    let mut room = legacy_room(creator.pubkey(), vec![creator.pubkey(), player.pubkey()], 0);
+    room.status = GameStatus::InProgress;
+    room.game_started_at = Some(0);
+    let mut program_test = program_test();
+    program_test.add_account(room_address, legacy_room_account(&room, legacy_rent() + 2 * ENTRY_FEE));
+    program_test.add_account(creator.pubkey(), Account::new(STARTING_BALANCE, 0, &solana_program::system_program::ID));
+    let mut env = TestEnv::start(program_test).await;
+
+    let end_game = instruction::end_game_unanimous(&program_id(), &creator.pubkey(), &room_address, &player.pubkey(), &[player.pubkey()]);
+    let result = env.process(std::slice::from_ref(&end_game), &[&creator, &player]).await;
+    assert_instruction_error(result, InstructionError::AccountDataTooSmall);
+
+    // Z System Programem podpisujący dopłaca czynsz i wynik zostaje zapisany
+    env.process(&[instruction::with_room_resize(end_game)], &[&creator, &player]).await.unwrap();
+    let room_rent = Rent::default().minimum_balance(GameRoom::space(4));
+    assert_eq!(env.lamports(&creator.pubkey()).await, STARTING_BALANCE - (room_rent - legacy_rent()));
+    assert_eq!(env.room(&room_address).await.status, GameStatus::Completed);
+}
+
+/// Konto pokoju z wersji 1 lub 2 - jak w pierwszej wersji programu ma stały rozmiar `LegacyGameRoom::SIZE`
+fn pre_release_room_account(version: u8, payload: &[u8]) -> Account {
+    let mut account = versioned_room_account(version, payload, program_id());
+    account.data.truncate(LegacyGameRoom::SIZE);
+    account.lamports = legacy_rent() + 2 * ENTRY_FEE;
+    account
+}
+
+#[tokio::test]
+async fn v1_waiting_room_is_cancelled_with_refunds() {
+    let creator = Keypair::new();
+    let player = Keypair::new();
+    let (room_address, _) = instruction::legacy_room_address(&program_id(), &creator.pubkey(), 2);
+    let payload = v1_room(creator.pubkey(), vec![creator.pubkey(), player.pubkey()], 2).try_to_vec().unwrap();
+    let mut program_test = program_test();
+    program_test.add_account(room_address, pre_release_room_account(1, &payload));
+    let mut env = TestEnv::start(program_test).await;
+
+    let keys = [creator.pubkey(), player.pubkey()];
+    env.process(&[cancel_room_ix(&creator.pubkey(), &room_address, &keys)], &[&creator]).await.unwrap();
+
+    assert_eq!(env.lamports(&player.pubkey()).await, ENTRY_FEE);
+    assert!(env.account(&room_address).await.is_none());
+}
+
+#[tokio::test]
+async fn v2_completed_room_pays_out_winner() {
+    let creator = Keypair::new();
+    let winner = Keypair::new();
+    let (room_address, bump) = instruction::legacy_room_address(&program_id(), &creator.pubkey(), 0);
+    let room = GameRoomV2 {
+        creator: creator.pubkey(),
+        max_players: 4,
+        entry_fee_lamports: ENTRY_FEE,
+        players: vec![creator.pubkey(), winner.pubkey()],
+        status: GameStatus::Completed,
+        winner: Some(winner.pubkey()),
+        created_at: 0,
+        game_started_at: Some(0),
+        game_ended_at: Some(0),
+        prize_claimed: false,
+        game_id: [0u8; 64],
+        room_slot: 0,
+        result_authority: Pubkey::new_unique(),
+        bump,
+    };
+    let mut program_test = program_test();
+    program_test.add_account(room_address, pre_release_room_account(2, &room.try_to_vec().unwrap()));
+    let mut env = TestEnv::start(program_test).await;
+    let fee_recipient = env.fee_recipient;
+
+    let ix = claim_prize_ix(&winner.pubkey(), &room_address, &fee_recipient, &creator.pubkey());
+    env.process(&[ix], &[&winner]).await.unwrap();
+
+    let platform_fee = 2 * ENTRY_FEE * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(env.lamports(&winner.pubkey()).await, 2 * ENTRY_FEE - platform_fee);
+    assert!(env.account(&room_address).await.is_none());
+}
+
+#[tokio::test]
+async fn room_from_unknown_layout_version_is_rejected() {
+    let creator = Pubkey::new_unique();
+    let room_address = room_pda(&creator, 0);
+    let mut program_test = program_test();
+    let payload = waiting_room(creator, 0).try_to_vec().unwrap();
+    program_test.add_account(room_address, versioned_room_account(GameRoom::VERSION + 1, &payload, program_id()));
+    let mut env = TestEnv::start(program_test).await;
+
+    let player = env.funded_keypair(1_000_000_000).await;
+    let result = env.process(&[join_room_ix(&player.pubkey(), &room_address)], &[&player]).await;
+
+    assert_uno_error(result, UnoError::UnsupportedAccountVersion);
+}
+
+#[tokio::test]
+async fn config_from_first_layout_version_is_migrated() {
+    let mut env = TestEnv::start(program_test()).await;
+    let mut account = env.account(&config_pda()).await.unwrap();
+    let (_, payload) = unpack_account_data(&account.data, &ProgramConfig::DISCRIMINATOR).unwrap();
+    let config = ProgramConfig::try_from_slice(payload).unwrap();
+    let v1 = ProgramConfigV1 {
+        admin: config.admin,
+        fee_recipient: config.params.fee_recipient,
+        fee_basis_points: config.params.fee_basis_points,
+        min_entry_fee_lamports: config.params.min_entry_fee_lamports,
+        max_entry_fee_lamports: config.params.max_entry_fee_lamports,
+        max_players: config.params.max_players,
+        result_authority: config.params.result_authority,
+        abandon_timeout_secs: config.params.abandon_timeout_secs,
+        bump: config.bump,
+    };
+    account.data.fill(0);
+    pack_account_data(&mut account.data, &ProgramConfig::DISCRIMINATOR, 1, &v1.try_to_vec().unwrap()).unwrap();
+    env.context.set_account(&config_pda(), &account.into());
+
+    // Konfiguracja w starym układzie nadal obsługuje tworzenie pokojów
+    let creator = env.funded_keypair(1_000_000_000).await;
+    env.process(&[create_room_ix(&creator.pubkey(), 2, ENTRY_FEE, 0)], &[&creator]).await.unwrap();
+
+    // Aktualizacja zapisuje konfigurację w bieżącym układzie
+    let admin = config.admin;
+    env.process(&[update_config_ix(&admin, admin, config.params)], &[]).await.unwrap();
+    let account = env.account(&config_pda()).await.unwrap();
+    let (version, _) = unpack_account_data(&account.data, &ProgramConfig::DISCRIMINATOR).unwrap();
+    assert_eq!(version, ProgramConfig::VERSION);
+}
+
+#[tokio::test]
+async fn room_data_in_config_account_is_rejected() {
+    let mut env = TestEnv::start(program_test()).await;
+    let player = env.funded_keypair(1_000_000_000).await;
+
+    // Konto konfiguracji należy do programu, ale ma inny dyskryminator
+    let result = env.process(&[join_room_ix(&player.pubkey(), &config_pda())], &[&player]).await;
+
+    assert_uno_error(result, UnoError::InvalidAccountType);
+}
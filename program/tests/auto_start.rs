@@ -0,0 +1,68 @@
+// Testy pokojów "sit and go": gra startuje przy dołączeniu ostatniego gracza
+
+mod common;
+
+use common::*;
+use solana_program::{instruction::InstructionError, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+use uno_solana::{
+    auto_start_game_id,
+    error::UnoError,
+    instruction::{self, RoomOptions},
+    GameStatus,
+};
+
+async fn auto_start_room(env: &mut TestEnv, max_players: u8) -> (Keypair, Pubkey) {
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let options = RoomOptions { auto_start: true, ..RoomOptions::default() };
+    let ix = instruction::create_room(&program_id(), &creator.pubkey(), max_players, ENTRY_FEE, 0, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+    (creator, address)
+}
+
+
+#[tokio::test]
+async fn room_starts_when_last_seat_is_taken() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (creator, address) = auto_start_room(&mut env, 3).await;
+
+    let first = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_auto_start_ix(&first.pubkey(), &address)], &[&first]).await.unwrap();
+    assert_eq!(env.room(&address).await.status, GameStatus::WaitingForPlayers);
+
+    let last = env.funded_keypair(STARTING_BALANCE).await;
+    let logs = env.process_with_logs(&[join_auto_start_ix(&last.pubkey(), &address)], &[&last]).await;
+
+    let room = env.room(&address).await;
+    assert_eq!(room.status, GameStatus::InProgress);
+    assert_eq!(room.get_game_id(), auto_start_game_id(&address));
+    assert!(room.game_started_at.is_some());
+    assert_ne!(room.deck_seed, [0u8; 32]);
+
+    assert_eq!(room.players, vec![creator.pubkey(), first.pubkey(), last.pubkey()]);
+
+    // Natywny runtime testów nie przekazuje `sol_log_data`, więc sprawdzamy log z ID gry
+    let started_log = format!("Program log: Gra UNO rozpoczęta automatycznie. Off-chain ID: {}", auto_start_game_id(&address));
+    assert!(logs.contains(&started_log));
+}
+
+#[tokio::test]
+async fn auto_start_room_rejects_manual_start_and_seed_commitments() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (creator, address) = auto_start_room(&mut env, 3).await;
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&player.pubkey(), &address)], &[&player]).await.unwrap();
+
+    let result = env.process(&[start_game_ix(&creator.pubkey(), &address, "g")], &[&creator]).await;
+    assert_uno_error(result, UnoError::AutoStartRoom);
+
+    let ix = commit_seed_ix(&player.pubkey(), &address, &test_seed(&player.pubkey()));
+    let result = env.process(&[ix], &[&player]).await;
+    assert_uno_error(result, UnoError::AutoStartRoom);
+
+    // Dołączenie zapełniające pokój musi przekazać SlotHashes
+    let last = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[join_room_ix(&last.pubkey(), &address)], &[&last]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+}
@@ -0,0 +1,102 @@
+// Testy anulowania pokoju: zwrot wpisowego wszystkim graczom i zamknięcie konta
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use uno_solana::{error::UnoError, GameRoom};
+
+async fn assert_cancel_refunds_everyone(player_count: usize) {
+    let mut env = TestEnv::start(program_test()).await;
+    let (room_address, mut players) = room_with_players(&mut env, player_count as u8, player_count - 1).await;
+    let creator = players.remove(0);
+
+    let room_balance = env.lamports(&room_address).await;
+    let creator_balance = env.lamports(&creator.pubkey()).await;
+    for player in &players {
+        assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE - ENTRY_FEE);
+    }
+
+    let player_keys: Vec<_> = players.iter().map(|p| p.pubkey()).collect();
+    env.process(&[cancel_room_ix(&creator.pubkey(), &room_address, &player_keys)], &[&creator]).await.unwrap();
+
+    for player in &players {
+        assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE);
+    }
+    // Twórca odzyskuje swoje wpisowe oraz czynsz za konto pokoju
+    let refunded_to_others = ENTRY_FEE * players.len() as u64;
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_balance + room_balance - refunded_to_others);
+    assert!(env.account(&room_address).await.is_none());
+}
+
+#[tokio::test]
+async fn cancel_refunds_two_players() {
+    assert_cancel_refunds_everyone(2).await;
+}
+
+#[tokio::test]
+async fn cancel_refunds_three_players() {
+    assert_cancel_refunds_everyone(3).await;
+}
+
+#[tokio::test]
+async fn cancel_refunds_four_players() {
+    assert_cancel_refunds_everyone(4).await;
+}
+
+#[tokio::test]
+async fn cancel_refunds_ten_players() {
+    assert_cancel_refunds_everyone(10).await;
+}
+
+#[tokio::test]
+async fn room_account_is_sized_for_max_players() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (room_address, _) = room_with_players(&mut env, 10, 9).await;
+
+    let account = env.account(&room_address).await.unwrap();
+    assert_eq!(account.data.len(), GameRoom::space(10));
+    assert!(GameRoom::space(2) < GameRoom::space(10));
+    assert_eq!(env.room(&room_address).await.players.len(), 10);
+}
+
+#[tokio::test]
+async fn cancel_rejects_refund_accounts_out_of_order() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (room_address, mut players) = room_with_players(&mut env, 3, 2).await;
+    let creator = players.remove(0);
+
+    let swapped = [players[1].pubkey(), players[0].pubkey()];
+    let result = env.process(&[cancel_room_ix(&creator.pubkey(), &room_address, &swapped)], &[&creator]).await;
+
+    assert_uno_error(result, UnoError::RefundAccountMismatch);
+}
+
+#[tokio::test]
+async fn cancel_rejects_non_host() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (room_address, mut players) = room_with_players(&mut env, 2, 1).await;
+    let creator = players.remove(0);
+
+    let result = env
+        .process(&[cancel_room_ix(&players[0].pubkey(), &room_address, &[creator.pubkey()])], &[&players[0]])
+        .await;
+
+    assert_uno_error(result, UnoError::NotRoomHost);
+}
+
+#[tokio::test]
+async fn cancelled_room_nonce_is_not_reused() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (room_address, mut players) = room_with_players(&mut env, 2, 0).await;
+    let creator = players.remove(0);
+
+    env.process(&[cancel_room_ix(&creator.pubkey(), &room_address, &[])], &[&creator]).await.unwrap();
+    let result = env.process(&[create_room_ix(&creator.pubkey(), 2, ENTRY_FEE, 0)], &[&creator]).await;
+    assert_uno_error(result, UnoError::StaleRoomNonce);
+
+    env.process(&[create_room_ix(&creator.pubkey(), 2, ENTRY_FEE, 1)], &[&creator]).await.unwrap();
+    let room = env.room(&room_pda(&creator.pubkey(), 1)).await;
+    assert_eq!(room.players, vec![creator.pubkey()]);
+    assert!(env.account(&room_address).await.is_none());
+}
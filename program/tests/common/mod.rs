@@ -0,0 +1,395 @@
+// Wspólne narzędzia dla testów integracyjnych programu UNO
+
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::clock::Clock,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use uno_solana::{
+    error::UnoError,
+    instruction::{self, RoomOptions},
+    pack_account_data, process_instruction,
+    rules::Move,
+    unpack_account_data,
+    ConfigParams, CreatorState, GameRoom, LegacyGameRoom, MAX_PLAYERS_LIMIT,
+};
+
+pub const ENTRY_FEE: u64 = 100_000_000;
+pub const MIN_ENTRY_FEE: u64 = 1_000;
+pub const FEE_BASIS_POINTS: u16 = 500;
+pub const ABANDON_TIMEOUT_SECS: i64 = 3_600;
+pub const HOST_INACTIVITY_SECS: i64 = 900;
+/// Saldo, z którym startuje każde konto gracza w testach
+pub const STARTING_BALANCE: u64 = 1_000_000_000;
+
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array([7u8; 32])
+}
+
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("uno_solana", program_id(), processor!(process_instruction));
+    program_test.prefer_bpf(false);
+    program_test
+}
+
+pub fn config_pda() -> Pubkey {
+    instruction::config_address(&program_id()).0
+}
+
+pub fn room_pda(creator: &Pubkey, room_nonce: u64) -> Pubkey {
+    room_pda_and_bump(creator, room_nonce).0
+}
+
+pub fn room_pda_and_bump(creator: &Pubkey, room_nonce: u64) -> (Pubkey, u8) {
+    instruction::room_address(&program_id(), creator, room_nonce)
+}
+
+pub fn config_params(fee_recipient: Pubkey, result_authority: Pubkey) -> ConfigParams {
+    ConfigParams {
+        fee_recipient,
+        fee_basis_points: FEE_BASIS_POINTS,
+        min_entry_fee_lamports: MIN_ENTRY_FEE,
+        max_entry_fee_lamports: 10_000_000_000,
+        max_players: MAX_PLAYERS_LIMIT,
+        result_authority,
+        abandon_timeout_secs: ABANDON_TIMEOUT_SECS,
+        host_inactivity_secs: HOST_INACTIVITY_SECS,
+    }
+}
+
+// Skróty do builderów z `uno_solana::instruction` z ustalonym `program_id` testów
+
+pub fn initialize_config_ix(admin: &Pubkey, params: ConfigParams) -> Instruction {
+    instruction::initialize_config(&program_id(), admin, params)
+}
+
+pub fn create_room_ix(creator: &Pubkey, max_players: u8, entry_fee_lamports: u64, room_nonce: u64) -> Instruction {
+    instruction::create_room(&program_id(), creator, max_players, entry_fee_lamports, room_nonce, RoomOptions::default())
+}
+
+pub fn join_room_ix(player: &Pubkey, room: &Pubkey) -> Instruction {
+    instruction::join_room(&program_id(), player, room)
+}
+
+pub fn start_game_ix(initiator: &Pubkey, room: &Pubkey, game_id: &str) -> Instruction {
+    instruction::start_game(&program_id(), initiator, room, game_id)
+}
+
+pub fn end_game_ix(authority: &Pubkey, room: &Pubkey, winner: Pubkey) -> Instruction {
+    instruction::end_game(&program_id(), authority, room, &winner)
+}
+
+pub fn claim_prize_ix(winner: &Pubkey, room: &Pubkey, fee_recipient: &Pubkey, creator: &Pubkey) -> Instruction {
+    instruction::claim_prize(&program_id(), winner, room, creator, fee_recipient)
+}
+
+pub fn leave_room_ix(player: &Pubkey, room: &Pubkey) -> Instruction {
+    instruction::leave_room(&program_id(), player, room)
+}
+
+pub fn reclaim_abandoned_ix(initiator: &Pubkey, room: &Pubkey, players: &[Pubkey]) -> Instruction {
+    instruction::reclaim_abandoned(&program_id(), initiator, room, players)
+}
+
+pub fn update_config_ix(admin: &Pubkey, new_admin: Pubkey, params: ConfigParams) -> Instruction {
+    instruction::update_config(&program_id(), admin, &new_admin, params)
+}
+
+pub fn commit_seed_ix(player: &Pubkey, room: &Pubkey, seed: &[u8; 32]) -> Instruction {
+    instruction::commit_seed(&program_id(), player, room, seed)
+}
+
+pub fn reveal_seed_ix(player: &Pubkey, room: &Pubkey, seed: &[u8; 32]) -> Instruction {
+    instruction::reveal_seed(&program_id(), player, room, seed)
+}
+
+/// Ziarno talii używane w testach przez danego gracza
+pub fn test_seed(player: &Pubkey) -> [u8; 32] {
+    player.to_bytes()
+}
+
+pub fn settle_ix(initiator: &Pubkey, room: &Pubkey, moves: &[Move], other_players: &[Pubkey]) -> Instruction {
+    instruction::settle_with_transcript(&program_id(), initiator, room, moves, other_players)
+}
+
+pub fn cancel_room_ix(creator: &Pubkey, room: &Pubkey, players: &[Pubkey]) -> Instruction {
+    instruction::cancel_room(&program_id(), creator, room, players)
+}
+
+pub fn forfeit_ix(player: &Keypair, room: &Pubkey) -> Instruction {
+    instruction::forfeit(&program_id(), &player.pubkey(), room)
+}
+
+pub fn kick_ix(creator: &Keypair, room: &Pubkey, player: &Pubkey, ban: bool) -> Instruction {
+    instruction::kick_player(&program_id(), &creator.pubkey(), room, player, ban)
+}
+
+pub fn set_ready_ix(player: &Keypair, room: &Pubkey, ready: bool) -> Instruction {
+    instruction::set_ready(&program_id(), &player.pubkey(), room, ready)
+}
+
+pub fn transfer_host_ix(signer: &Keypair, room: &Pubkey, new_host: &Pubkey) -> Instruction {
+    instruction::transfer_host(&program_id(), &signer.pubkey(), room, new_host)
+}
+
+/// `JoinRoom` z kontem SlotHashes potrzebnym, gdy dołączenie zapełnia pokój z `auto_start`
+pub fn join_auto_start_ix(player: &Pubkey, room: &Pubkey) -> Instruction {
+    instruction::with_slot_hashes(join_room_ix(player, room))
+}
+
+/// Środowisko testowe z zainicjalizowaną konfiguracją programu
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub fee_recipient: Pubkey,
+    pub result_authority: Keypair,
+}
+
+/// Zapisuje konto ProgramData programu z podanym upgrade authority
+/// (`processor!` uruchamia program natywnie, więc loader nie tworzy tego konta sam)
+pub fn set_upgrade_authority(context: &mut ProgramTestContext, upgrade_authority: &Pubkey) {
+    let state = UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(*upgrade_authority) };
+    let account = Account::new_data(1_000_000_000, &state, &bpf_loader_upgradeable::ID).unwrap();
+    context.set_account(&instruction::program_data_address(&program_id()), &account.into());
+}
+
+impl TestEnv {
+    pub async fn start(program_test: ProgramTest) -> Self {
+        let mut context = program_test.start_with_context().await;
+        let fee_recipient = Pubkey::new_unique();
+        let result_authority = Keypair::new();
+        let payer = context.payer.pubkey();
+        set_upgrade_authority(&mut context, &payer);
+
+        let ix = initialize_config_ix(&payer, config_params(fee_recipient, result_authority.pubkey()));
+        let mut env = Self { context, fee_recipient, result_authority };
+        env.process(&[ix], &[]).await.unwrap();
+        env
+    }
+
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Wykonuje transakcję, która musi się powieść, i zwraca jej logi
+    pub async fn process_with_logs(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Vec<String> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let outcome = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        outcome.result.unwrap();
+        outcome.metadata.expect("transaction metadata").log_messages
+    }
+
+    /// Tworzy nowe konto z zadanym saldem (w SOL-ach testowych)
+    pub async fn funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let ix = system_instruction::transfer(&self.context.payer.pubkey(), &keypair.pubkey(), lamports);
+        self.process(&[ix], &[]).await.unwrap();
+        keypair
+    }
+
+    /// Przesuwa zegar klastra o podaną liczbę sekund
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(*address).await.unwrap()
+    }
+
+    pub async fn room(&mut self, address: &Pubkey) -> GameRoom {
+        let account = self.account(address).await.expect("room account exists");
+        decode_room(&account.data)
+    }
+
+    /// Licznik pokojów twórcy (brak konta przed pierwszym pokojem)
+    pub async fn creator_state(&mut self, creator: &Pubkey) -> Option<CreatorState> {
+        let address = instruction::creator_state_address(&program_id(), creator);
+        let account = self.account(&address).await?;
+        let (_, payload) = unpack_account_data(&account.data, &CreatorState::DISCRIMINATOR).unwrap();
+        Some(CreatorState::try_from_slice(payload).unwrap())
+    }
+
+    /// Przeprowadza fazę commit-reveal: wszyscy gracze składają zobowiązania, potem ujawniają ziarna
+    pub async fn reveal_seeds(&mut self, room: &Pubkey, players: &[&Keypair]) {
+        for player in players {
+            let ix = commit_seed_ix(&player.pubkey(), room, &test_seed(&player.pubkey()));
+            self.process(&[ix], &[player]).await.unwrap();
+        }
+        for player in players {
+            let ix = reveal_seed_ix(&player.pubkey(), room, &test_seed(&player.pubkey()));
+            self.process(&[ix], &[player]).await.unwrap();
+        }
+    }
+
+    /// Wszyscy podani gracze zgłaszają gotowość do startu
+    pub async fn ready_up(&mut self, room: &Pubkey, players: &[&Keypair]) {
+        for player in players {
+            let ix = instruction::set_ready(&program_id(), &player.pubkey(), room, true);
+            self.process(&[ix], &[player]).await.unwrap();
+        }
+    }
+
+    /// Przygotowuje pokój do `StartGame`: faza commit-reveal i gotowość wszystkich graczy
+    pub async fn prepare_start(&mut self, room: &Pubkey, players: &[&Keypair]) {
+        self.reveal_seeds(room, players).await;
+        self.ready_up(room, players).await;
+    }
+
+    /// Nadpisuje dane istniejącego pokoju (np. żeby ustalić ziarno talii)
+    pub async fn set_room(&mut self, address: &Pubkey, room: &GameRoom) {
+        let mut account = self.account(address).await.expect("room account exists");
+        pack_account_data(&mut account.data, &GameRoom::DISCRIMINATOR, GameRoom::VERSION, &room.try_to_vec().unwrap())
+            .unwrap();
+        self.context.set_account(address, &account.into());
+    }
+}
+
+/// Dołącza do pokoju `count` nowych graczy z saldem `STARTING_BALANCE`
+pub async fn join_players(env: &mut TestEnv, room: &Pubkey, count: usize) -> Vec<Keypair> {
+    let mut players = Vec::new();
+    for _ in 0..count {
+        let player = env.funded_keypair(STARTING_BALANCE).await;
+        env.process(&[join_room_ix(&player.pubkey(), room)], &[&player]).await.unwrap();
+        players.push(player);
+    }
+    players
+}
+
+/// Tworzy pokój SOL (nonce 0) i dołącza `other_players` graczy.
+/// Zwraca adres pokoju i graczy w kolejności z `players` pokoju - twórca jest pierwszy.
+pub async fn room_with_players(env: &mut TestEnv, max_players: u8, other_players: usize) -> (Pubkey, Vec<Keypair>) {
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[create_room_ix(&creator.pubkey(), max_players, ENTRY_FEE, 0)], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+
+    let mut players = vec![creator];
+    players.extend(join_players(env, &address, other_players).await);
+    (address, players)
+}
+
+/// Pokój SOL utworzony przez `open_room` - `players` nie obejmuje twórcy
+pub struct Room {
+    pub creator: Keypair,
+    pub players: Vec<Keypair>,
+    pub address: Pubkey,
+}
+
+impl Room {
+    pub fn all_keys(&self) -> Vec<Pubkey> {
+        std::iter::once(self.creator.pubkey())
+            .chain(self.players.iter().map(|p| p.pubkey()))
+            .collect()
+    }
+
+    pub fn signers(&self) -> Vec<&Keypair> {
+        std::iter::once(&self.creator).chain(&self.players).collect()
+    }
+}
+
+pub async fn open_room(env: &mut TestEnv, max_players: u8, other_players: usize) -> Room {
+    let (address, mut players) = room_with_players(env, max_players, other_players).await;
+    let creator = players.remove(0);
+    Room { creator, players, address }
+}
+
+/// Przygotowuje start wszystkich graczy i rozpoczyna grę jako twórca
+pub async fn start_room(env: &mut TestEnv, room: &Room) {
+    env.prepare_start(&room.address, &room.signers()).await;
+    env.process(&[start_game_ix(&room.creator.pubkey(), &room.address, "game-1")], &[&room.creator]).await.unwrap();
+}
+
+pub async fn started_room(env: &mut TestEnv, max_players: u8, other_players: usize) -> Room {
+    let room = open_room(env, max_players, other_players).await;
+    start_room(env, &room).await;
+    room
+}
+
+pub fn room_rent(max_players: u8) -> u64 {
+    Rent::default().minimum_balance(GameRoom::space(max_players))
+}
+
+// Metody `GameRoom` logują przez `msg!`, co poza wywołaniem programu kończy się
+// panikiem, dlatego testy kodują i dekodują dane pokoju bezpośrednio.
+pub fn decode_room(data: &[u8]) -> GameRoom {
+    let (version, payload) = unpack_account_data(data, &GameRoom::DISCRIMINATOR).unwrap();
+    assert_eq!(version, GameRoom::VERSION);
+    GameRoom::try_from_slice(payload).unwrap()
+}
+
+/// Konto z danymi pokoju przygotowane do wstrzyknięcia przez `ProgramTest::add_account`
+pub fn room_account(room: &GameRoom, owner: Pubkey) -> Account {
+    versioned_room_account(GameRoom::VERSION, &room.try_to_vec().unwrap(), owner)
+}
+
+/// Konto pokoju zapisane w dowolnej (także historycznej) wersji układu
+pub fn versioned_room_account(version: u8, payload: &[u8], owner: Pubkey) -> Account {
+    let mut data = vec![0u8; GameRoom::space(MAX_PLAYERS_LIMIT)];
+    pack_account_data(&mut data, &GameRoom::DISCRIMINATOR, version, payload).unwrap();
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Konto pokoju z pierwszej wersji programu (4 bajty rozmiaru i dane Borsh, bez nagłówka)
+pub fn legacy_room_account(room: &LegacyGameRoom, lamports: u64) -> Account {
+    let payload = room.try_to_vec().unwrap();
+    let mut data = vec![0u8; LegacyGameRoom::SIZE];
+    data[..LegacyGameRoom::HEADER_SIZE].copy_from_slice(&(payload.len() as u32).to_le_bytes());
+    data[LegacyGameRoom::HEADER_SIZE..LegacyGameRoom::HEADER_SIZE + payload.len()].copy_from_slice(&payload);
+    Account {
+        lamports,
+        data,
+        owner: program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn assert_uno_error(result: Result<(), BanksClientError>, expected: UnoError) {
+    assert_instruction_error(result, InstructionError::Custom(expected as u32));
+}
+
+pub fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(_, error) => assert_eq!(error, expected),
+        other => panic!("unexpected transaction error: {:?}", other),
+    }
+}
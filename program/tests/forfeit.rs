@@ -0,0 +1,101 @@
+// Testy poddawania trwającej gry i automatycznego zakończenia po odejściu rywali
+
+mod common;
+
+use common::*;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use uno_solana::{
+    error::UnoError,
+    instruction::{self, RoomOptions},
+    GameStatus,
+};
+
+#[tokio::test]
+async fn last_remaining_player_wins() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, 2).await;
+    let keys = room.all_keys();
+
+    env.process(&[forfeit_ix(&room.players[1], &room.address)], &[&room.players[1]]).await.unwrap();
+    let stored = env.room(&room.address).await;
+    assert_eq!(stored.status, GameStatus::InProgress);
+    assert_eq!(stored.forfeited, vec![keys[2]]);
+
+    let result = env.process(&[forfeit_ix(&room.players[1], &room.address)], &[&room.players[1]]).await;
+    assert_uno_error(result, UnoError::PlayerForfeited);
+
+    // Poddany gracz nie może zostać ogłoszony zwycięzcą
+    let authority = env.result_authority.insecure_clone();
+    let result = env.process(&[end_game_ix(&authority.pubkey(), &room.address, keys[2])], &[&authority]).await;
+    assert_uno_error(result, UnoError::PlayerForfeited);
+
+    env.process(&[forfeit_ix(&room.creator, &room.address)], &[&room.creator]).await.unwrap();
+    let stored = env.room(&room.address).await;
+    assert_eq!(stored.status, GameStatus::Completed);
+    assert_eq!(stored.winner, Some(keys[1]));
+    assert_eq!(stored.placements, vec![keys[1], keys[0], keys[2]]);
+    assert!(stored.game_ended_at.is_some());
+
+    // Zwycięzca odbiera pulę jak po zwykłym zakończeniu gry
+    let winner = &room.players[0];
+    let fee_recipient = env.fee_recipient;
+    let before = env.lamports(&keys[1]).await;
+    env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &keys[0])], &[winner])
+        .await
+        .unwrap();
+    let total_prize = ENTRY_FEE * 3;
+    assert_eq!(env.lamports(&keys[1]).await, before + total_prize - total_prize * FEE_BASIS_POINTS as u64 / 10_000);
+}
+
+#[tokio::test]
+async fn forfeit_requires_running_game_and_seat() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 2, 1).await;
+
+    let result = env.process(&[forfeit_ix(&room.players[0], &room.address)], &[&room.players[0]]).await;
+    assert_uno_error(result, UnoError::GameNotInProgress);
+
+    start_room(&mut env, &room).await;
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[forfeit_ix(&outsider, &room.address)], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+}
+
+#[tokio::test]
+async fn ranked_result_places_forfeited_players_last() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, 2).await;
+    let keys = room.all_keys();
+    env.process(&[forfeit_ix(&room.creator, &room.address)], &[&room.creator]).await.unwrap();
+
+    let authority = env.result_authority.insecure_clone();
+    let ranked = |placements: &[Pubkey]| instruction::end_game_ranked(&program_id(), &authority.pubkey(), &room.address, placements, &[]);
+    let result = env.process(&[ranked(&[keys[1], keys[0], keys[2]])], &[&authority]).await;
+    assert_uno_error(result, UnoError::InvalidFinishingOrder);
+
+    env.process(&[ranked(&[keys[2], keys[1], keys[0]])], &[&authority]).await.unwrap();
+    assert_eq!(env.room(&room.address).await.winner, Some(keys[2]));
+}
+
+#[tokio::test]
+async fn ranked_result_rejects_forfeited_player_in_paid_place() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let options = RoomOptions { payout_bps: vec![5_000, 3_000, 2_000], ..RoomOptions::default() };
+    let ix = instruction::create_room(&program_id(), &creator.pubkey(), 3, ENTRY_FEE, 0, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+    let players = join_players(&mut env, &address, 2).await;
+    let room = Room { creator, players, address };
+    start_room(&mut env, &room).await;
+    let keys = room.all_keys();
+    env.process(&[forfeit_ix(&room.players[1], &room.address)], &[&room.players[1]]).await.unwrap();
+
+    // Poddany gracz stoi na ostatnim miejscu, ale to miejsce też jest płatne
+    let authority = env.result_authority.insecure_clone();
+    let ix = instruction::end_game_ranked(&program_id(), &authority.pubkey(), &room.address, &keys, &[]);
+    let result = env.process(&[ix], &[&authority]).await;
+    assert_uno_error(result, UnoError::PlayerForfeited);
+    assert_eq!(env.room(&room.address).await.status, GameStatus::InProgress);
+}
@@ -0,0 +1,128 @@
+// Testy przekazywania roli gospodarza: ręczne przekazanie, przejęcie po nieaktywności i powrót do twórcy
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::Signer;
+use uno_solana::{error::UnoError, instruction};
+
+
+#[tokio::test]
+async fn new_host_cancels_and_creator_keeps_rent() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 3, 2).await;
+    let creator = players.remove(0);
+    let host = &players[0];
+
+    env.process(&[transfer_host_ix(&creator, &address, &host.pubkey())], &[&creator]).await.unwrap();
+    let room = env.room(&address).await;
+    assert_eq!(room.host, host.pubkey());
+    assert_eq!(room.creator, creator.pubkey());
+    assert_eq!(address, room_pda(&creator.pubkey(), 0));
+
+    // Twórca nie jest już gospodarzem
+    let keys = [creator.pubkey(), host.pubkey(), players[1].pubkey()];
+    let result = env.process(&[cancel_room_ix(&creator.pubkey(), &address, &keys)], &[&creator]).await;
+    assert_uno_error(result, UnoError::NotRoomHost);
+
+    let room_balance = env.lamports(&address).await;
+    let creator_balance = env.lamports(&creator.pubkey()).await;
+    env.process(&[cancel_room_ix(&host.pubkey(), &address, &keys)], &[host]).await.unwrap();
+
+    for player in &players {
+        assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE);
+    }
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_balance + room_balance - 2 * ENTRY_FEE);
+    assert!(env.account(&address).await.is_none());
+}
+
+#[tokio::test]
+async fn player_takes_over_inactive_host() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 3, 2).await;
+    let creator = players.remove(0);
+    let player = &players[0];
+
+    let result = env.process(&[transfer_host_ix(player, &address, &player.pubkey())], &[player]).await;
+    assert_uno_error(result, UnoError::HostStillActive);
+
+    // Osoba spoza pokoju nie może przejąć roli nawet po czasie nieaktywności
+    env.advance_clock(HOST_INACTIVITY_SECS).await;
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[transfer_host_ix(&outsider, &address, &outsider.pubkey())], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotRoomHost);
+
+    let result = env.process(&[transfer_host_ix(player, &address, &outsider.pubkey())], &[player]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+
+    env.process(&[transfer_host_ix(player, &address, &player.pubkey())], &[player]).await.unwrap();
+    assert_eq!(env.room(&address).await.host, player.pubkey());
+
+    // Nowy gospodarz może ustawić kworum - twórca już nie
+    let result = env
+        .process(&[instruction::set_ready_quorum(&program_id(), &creator.pubkey(), &address, 2)], &[&creator])
+        .await;
+    assert_uno_error(result, UnoError::NotRoomHost);
+    env.process(&[instruction::set_ready_quorum(&program_id(), &player.pubkey(), &address, 2)], &[player])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn host_activity_postpones_takeover() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 2, 1).await;
+    let creator = players.remove(0);
+    let player = &players[0];
+
+    env.advance_clock(HOST_INACTIVITY_SECS - 10).await;
+    env.process(&[instruction::set_ready(&program_id(), &creator.pubkey(), &address, true)], &[&creator])
+        .await
+        .unwrap();
+    env.advance_clock(20).await;
+
+    let result = env.process(&[transfer_host_ix(player, &address, &player.pubkey())], &[player]).await;
+    assert_uno_error(result, UnoError::HostStillActive);
+}
+
+#[tokio::test]
+async fn host_seed_reveal_postpones_takeover() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 2, 1).await;
+    let creator = players.remove(0);
+    let player = &players[0];
+    for signer in [&creator, player] {
+        env.process(&[commit_seed_ix(&signer.pubkey(), &address, &test_seed(&signer.pubkey()))], &[signer])
+            .await
+            .unwrap();
+    }
+
+    env.advance_clock(HOST_INACTIVITY_SECS - 10).await;
+    env.process(&[reveal_seed_ix(&creator.pubkey(), &address, &test_seed(&creator.pubkey()))], &[&creator])
+        .await
+        .unwrap();
+    env.advance_clock(20).await;
+
+    let result = env.process(&[transfer_host_ix(player, &address, &player.pubkey())], &[player]).await;
+    assert_uno_error(result, UnoError::HostStillActive);
+
+    // Przejęcie zależy od `host_inactivity_secs`, a nie od dłuższego `abandon_timeout_secs`
+    env.advance_clock(HOST_INACTIVITY_SECS).await;
+    env.process(&[transfer_host_ix(player, &address, &player.pubkey())], &[player]).await.unwrap();
+    assert_eq!(env.room(&address).await.host, player.pubkey());
+}
+
+#[tokio::test]
+async fn leaving_host_hands_role_back_to_creator() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 3, 1).await;
+    let creator = players.remove(0);
+    let host = &players[0];
+
+    env.process(&[transfer_host_ix(&creator, &address, &host.pubkey())], &[&creator]).await.unwrap();
+    env.process(&[instruction::leave_room(&program_id(), &host.pubkey(), &address)], &[host]).await.unwrap();
+
+    let room = env.room(&address).await;
+    assert_eq!(room.host, creator.pubkey());
+    assert_eq!(room.players, vec![creator.pubkey()]);
+}
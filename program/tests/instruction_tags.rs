@@ -0,0 +1,84 @@
+// Numery tagów Borsh instrukcji są częścią formatu on-chain - frontend (`SolanaTransactions.js`)
+// koduje je na sztywno, więc nowe instrukcje dopisujemy wyłącznie na końcu `UnoInstruction`
+
+use borsh::BorshSerialize;
+use solana_program::pubkey::Pubkey;
+use uno_solana::{ConfigParams, RoomAccess, UnoInstruction};
+
+/// Oczekiwany tag każdej instrukcji - `match` bez `_` wymusza dopisanie tagu nowej instrukcji
+fn expected_tag(instruction: &UnoInstruction) -> u8 {
+    match instruction {
+        UnoInstruction::CreateRoom { .. } => 0,
+        UnoInstruction::JoinRoom => 1,
+        UnoInstruction::StartGame { .. } => 2,
+        UnoInstruction::EndGame { .. } => 3,
+        UnoInstruction::ClaimPrize => 4,
+        UnoInstruction::CancelRoom => 5,
+        UnoInstruction::InitializeConfig { .. } => 6,
+        UnoInstruction::UpdateConfig { .. } => 7,
+        UnoInstruction::ReclaimAbandoned => 8,
+        UnoInstruction::LeaveRoom => 9,
+        UnoInstruction::SettleWithTranscript { .. } => 10,
+        UnoInstruction::CommitSeed { .. } => 11,
+        UnoInstruction::RevealSeed { .. } => 12,
+        UnoInstruction::EndGameRanked { .. } => 13,
+        UnoInstruction::SetReady { .. } => 14,
+        UnoInstruction::SetReadyQuorum { .. } => 15,
+        UnoInstruction::KickPlayer { .. } => 16,
+        UnoInstruction::TransferHost { .. } => 17,
+        UnoInstruction::Forfeit => 18,
+        UnoInstruction::JoinPrivateRoom { .. } => 19,
+    }
+}
+
+fn params() -> ConfigParams {
+    ConfigParams {
+        fee_recipient: Pubkey::new_unique(),
+        fee_basis_points: 500,
+        min_entry_fee_lamports: 1,
+        max_entry_fee_lamports: 2,
+        max_players: 4,
+        result_authority: Pubkey::new_unique(),
+        abandon_timeout_secs: 1,
+        host_inactivity_secs: 1,
+    }
+}
+
+#[test]
+fn instruction_tags_are_stable() {
+    let instructions = vec![
+        UnoInstruction::CreateRoom {
+            max_players: 2,
+            entry_fee_lamports: 1,
+            room_nonce: 0,
+            mint: None,
+            payout_bps: vec![],
+            access: RoomAccess::Public,
+            auto_start: false,
+        },
+        UnoInstruction::JoinRoom,
+        UnoInstruction::StartGame { game_id: "g".to_string() },
+        UnoInstruction::EndGame { winner: Pubkey::new_unique() },
+        UnoInstruction::ClaimPrize,
+        UnoInstruction::CancelRoom,
+        UnoInstruction::InitializeConfig { params: params() },
+        UnoInstruction::UpdateConfig { new_admin: Pubkey::new_unique(), params: params() },
+        UnoInstruction::ReclaimAbandoned,
+        UnoInstruction::LeaveRoom,
+        UnoInstruction::SettleWithTranscript { moves: vec![] },
+        UnoInstruction::CommitSeed { commitment: [0; 32] },
+        UnoInstruction::RevealSeed { seed: [0; 32] },
+        UnoInstruction::EndGameRanked { placements: vec![] },
+        UnoInstruction::SetReady { ready: true },
+        UnoInstruction::SetReadyQuorum { quorum: 0 },
+        UnoInstruction::KickPlayer { player: Pubkey::new_unique(), ban: false },
+        UnoInstruction::TransferHost { new_host: Pubkey::new_unique() },
+        UnoInstruction::Forfeit,
+        UnoInstruction::JoinPrivateRoom { invite_secret: vec![1] },
+    ];
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        assert_eq!(expected_tag(instruction) as usize, index, "{:?}", instruction);
+        assert_eq!(instruction.try_to_vec().unwrap()[0], expected_tag(instruction), "{:?}", instruction);
+    }
+}
@@ -0,0 +1,90 @@
+// Testy moderacji oczekującego pokoju: wyrzucanie graczy i lista zbanowanych
+
+mod common;
+
+use common::*;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use uno_solana::{error::UnoError, MAX_BANNED_PLAYERS};
+
+
+#[tokio::test]
+async fn kicked_player_is_refunded_and_may_rejoin() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 3, 2).await;
+    let creator = players.remove(0);
+    let kicked = &players[0];
+
+    env.process(&[kick_ix(&creator, &address, &kicked.pubkey(), false)], &[&creator]).await.unwrap();
+
+    assert_eq!(env.lamports(&kicked.pubkey()).await, STARTING_BALANCE);
+    let room = env.room(&address).await;
+    assert_eq!(room.players, vec![creator.pubkey(), players[1].pubkey()]);
+    assert!(room.banned.is_empty());
+
+    env.process(&[join_room_ix(&kicked.pubkey(), &address)], &[kicked]).await.unwrap();
+    assert_eq!(env.room(&address).await.players.len(), 3);
+}
+
+#[tokio::test]
+async fn banned_player_cannot_rejoin() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 2, 1).await;
+    let creator = players.remove(0);
+    let banned = &players[0];
+
+    env.process(&[kick_ix(&creator, &address, &banned.pubkey(), true)], &[&creator]).await.unwrap();
+    assert_eq!(env.room(&address).await.banned, vec![banned.pubkey()]);
+
+    let result = env.process(&[join_room_ix(&banned.pubkey(), &address)], &[banned]).await;
+    assert_uno_error(result, UnoError::PlayerBanned);
+}
+
+#[tokio::test]
+async fn ban_list_is_bounded() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 2, 0).await;
+    let creator = players.remove(0);
+
+    for _ in 0..MAX_BANNED_PLAYERS {
+        let player = env.funded_keypair(STARTING_BALANCE).await;
+        env.process(&[join_room_ix(&player.pubkey(), &address)], &[&player]).await.unwrap();
+        env.process(&[kick_ix(&creator, &address, &player.pubkey(), true)], &[&creator]).await.unwrap();
+    }
+
+    // Przy pełnej liście można jeszcze wyrzucić gracza, ale już nie zbanować
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&player.pubkey(), &address)], &[&player]).await.unwrap();
+    let result = env.process(&[kick_ix(&creator, &address, &player.pubkey(), true)], &[&creator]).await;
+    assert_uno_error(result, UnoError::BanListFull);
+    env.process(&[kick_ix(&creator, &address, &player.pubkey(), false)], &[&creator]).await.unwrap();
+    assert_eq!(env.room(&address).await.banned.len(), MAX_BANNED_PLAYERS);
+}
+
+#[tokio::test]
+async fn kick_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, mut players) = room_with_players(&mut env, 2, 1).await;
+    let creator = players.remove(0);
+    let player = &players[0];
+
+    let result = env.process(&[kick_ix(player, &address, &creator.pubkey(), false)], &[player]).await;
+    assert_uno_error(result, UnoError::NotRoomHost);
+
+    let result = env.process(&[kick_ix(&creator, &address, &creator.pubkey(), false)], &[&creator]).await;
+    assert_uno_error(result, UnoError::CreatorCannotLeave);
+
+    let mut ix = kick_ix(&creator, &address, &player.pubkey(), false);
+    ix.accounts[2].pubkey = creator.pubkey();
+    let result = env.process(&[ix], &[&creator]).await;
+    assert_uno_error(result, UnoError::RefundAccountMismatch);
+
+    let outsider = Pubkey::new_unique();
+    let result = env.process(&[kick_ix(&creator, &address, &outsider, false)], &[&creator]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+
+    env.prepare_start(&address, &[&creator, player]).await;
+    env.process(&[start_game_ix(&creator.pubkey(), &address, "g")], &[&creator]).await.unwrap();
+    let result = env.process(&[kick_ix(&creator, &address, &player.pubkey(), false)], &[&creator]).await;
+    assert_uno_error(result, UnoError::RoomNotWaiting);
+}
@@ -0,0 +1,495 @@
+// Testy pełnego cyklu życia pokoju wraz z saldami oraz ścieżkami odrzucenia
+
+mod common;
+
+use common::*;
+use borsh::BorshDeserialize;
+use solana_program::{instruction::InstructionError, pubkey::Pubkey, rent::Rent};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use uno_solana::{error::UnoError, instruction, unpack_account_data, CreatorState, GameStatus, ProgramConfig};
+
+async fn completed_room(env: &mut TestEnv, winner_index: usize) -> Room {
+    let room = started_room(env, 3, 2).await;
+    let winner = room.all_keys()[winner_index];
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[end_game_ix(&authority.pubkey(), &room.address, winner)], &[&authority]).await.unwrap();
+    room
+}
+
+
+// Licznik pokojów twórcy zostaje po zamknięciu pokoju, więc jego czynsz nie wraca
+fn creator_state_rent() -> u64 {
+    Rent::default().minimum_balance(CreatorState::SIZE)
+}
+
+#[tokio::test]
+async fn full_game_pays_winner_platform_and_returns_rent() {
+    let mut env = TestEnv::start(program_test()).await;
+    let rent = room_rent(3);
+
+    // CreateRoom: twórca płaci czynsz i wpisowe
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[create_room_ix(&creator.pubkey(), 3, ENTRY_FEE, 0)], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+    assert_eq!(env.lamports(&creator.pubkey()).await, STARTING_BALANCE - creator_state_rent() - rent - ENTRY_FEE);
+    assert_eq!(env.lamports(&address).await, rent + ENTRY_FEE);
+
+    // JoinRoom: każdy gracz wpłaca wpisowe do PDA
+    let mut players = Vec::new();
+    for joined in 1..=2u64 {
+        let player = env.funded_keypair(STARTING_BALANCE).await;
+        env.process(&[join_room_ix(&player.pubkey(), &address)], &[&player]).await.unwrap();
+        assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE - ENTRY_FEE);
+        assert_eq!(env.lamports(&address).await, rent + ENTRY_FEE * (joined + 1));
+        players.push(player);
+    }
+
+    // Commit-reveal ziaren talii i StartGame: bez ruchu środków
+    env.prepare_start(&address, &[&creator, &players[0], &players[1]]).await;
+    env.process(&[start_game_ix(&players[0].pubkey(), &address, "game-1")], &[&players[0]]).await.unwrap();
+    let room = env.room(&address).await;
+    assert_eq!(room.status, GameStatus::InProgress);
+    assert_eq!(room.get_game_id(), "game-1");
+    assert_eq!(env.lamports(&address).await, rent + ENTRY_FEE * 3);
+
+    // EndGame: serwer gry ogłasza zwycięzcę
+    let winner = &players[1];
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[end_game_ix(&authority.pubkey(), &address, winner.pubkey())], &[&authority]).await.unwrap();
+    let room = env.room(&address).await;
+    assert_eq!(room.status, GameStatus::Completed);
+    assert_eq!(room.winner, Some(winner.pubkey()));
+    assert_eq!(env.lamports(&address).await, rent + ENTRY_FEE * 3);
+
+    // ClaimPrize: pula minus prowizja dla zwycięzcy, prowizja dla platformy, czynsz dla twórcy
+    let creator_before = env.lamports(&creator.pubkey()).await;
+    let fee_recipient = env.fee_recipient;
+    env.process(&[claim_prize_ix(&winner.pubkey(), &address, &fee_recipient, &creator.pubkey())], &[winner])
+        .await
+        .unwrap();
+
+    let total_prize = ENTRY_FEE * 3;
+    let platform_fee = total_prize * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(env.lamports(&winner.pubkey()).await, STARTING_BALANCE - ENTRY_FEE + total_prize - platform_fee);
+    assert_eq!(env.lamports(&players[0].pubkey()).await, STARTING_BALANCE - ENTRY_FEE);
+    assert_eq!(env.lamports(&fee_recipient).await, platform_fee);
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_before + rent);
+    assert!(env.account(&address).await.is_none());
+}
+
+#[tokio::test]
+async fn create_join_cancel_refunds_everyone() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 2, 1).await;
+    assert_eq!(env.lamports(&room.address).await, room_rent(2) + ENTRY_FEE * 2);
+
+    let others = [room.players[0].pubkey()];
+    env.process(&[cancel_room_ix(&room.creator.pubkey(), &room.address, &others)], &[&room.creator])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(&room.creator.pubkey()).await, STARTING_BALANCE - creator_state_rent());
+    assert_eq!(env.lamports(&room.players[0].pubkey()).await, STARTING_BALANCE);
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn create_room_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let key = creator.pubkey();
+
+    let result = env.process(&[create_room_ix(&key, 1, ENTRY_FEE, 0)], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidMaxPlayers);
+
+    let result = env.process(&[create_room_ix(&key, 11, ENTRY_FEE, 0)], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidMaxPlayers);
+
+    let result = env.process(&[create_room_ix(&key, 2, 10, 0)], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidEntryFee);
+
+    let result = env.process(&[create_room_ix(&key, 2, 20_000_000_000, 0)], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidEntryFee);
+
+    let result = env.process(&[create_room_ix(&key, 2, ENTRY_FEE, 1)], &[&creator]).await;
+    assert_uno_error(result, UnoError::StaleRoomNonce);
+
+    let mut ix = create_room_ix(&key, 2, ENTRY_FEE, 0);
+    ix.accounts[1].pubkey = room_pda(&key, 1);
+    let result = env.process(&[ix], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidRoomAddress);
+
+    let mut ix = create_room_ix(&key, 2, ENTRY_FEE, 0);
+    ix.accounts[4].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidConfigAccount);
+
+    let mut ix = create_room_ix(&key, 2, ENTRY_FEE, 0);
+    ix.accounts[5].pubkey = room_pda(&key, 0);
+    let result = env.process(&[ix], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidCreatorState);
+
+    let mut ix = create_room_ix(&key, 2, ENTRY_FEE, 0);
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&creator]).await;
+    assert_uno_error(result, UnoError::InvalidSystemProgram);
+
+    let mut ix = create_room_ix(&key, 2, ENTRY_FEE, 0);
+    ix.accounts[0].is_signer = false;
+    let result = env.process(&[ix], &[]).await;
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+}
+
+#[tokio::test]
+async fn creator_hosts_rooms_beyond_ten_with_counter() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    assert!(env.creator_state(&creator.pubkey()).await.is_none());
+
+    for room_nonce in 0..12 {
+        env.process(&[create_room_ix(&creator.pubkey(), 2, MIN_ENTRY_FEE, room_nonce)], &[&creator]).await.unwrap();
+    }
+
+    // Klient odnajduje wszystkie pokoje twórcy, licząc od zera do `next_room_nonce`
+    let state = env.creator_state(&creator.pubkey()).await.unwrap();
+    assert_eq!(state.creator, creator.pubkey());
+    assert_eq!(state.next_room_nonce, 12);
+    for room_nonce in 0..state.next_room_nonce {
+        let room = env.room(&room_pda(&creator.pubkey(), room_nonce)).await;
+        assert_eq!(room.room_nonce, Some(room_nonce));
+    }
+}
+
+#[tokio::test]
+async fn prefunded_pdas_do_not_block_room_creation() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let key = creator.pubkey();
+    let room = room_pda(&key, 0);
+    let state = instruction::creator_state_address(&program_id(), &key);
+
+    // Adresy PDA są przewidywalne - każdy może wcześniej przesłać na nie lamporty
+    let griefer = env.funded_keypair(STARTING_BALANCE).await;
+    let donation = Rent::default().minimum_balance(0);
+    let ixs = [
+        system_instruction::transfer(&griefer.pubkey(), &room, donation),
+        system_instruction::transfer(&griefer.pubkey(), &state, donation),
+    ];
+    env.process(&ixs, &[&griefer]).await.unwrap();
+
+    env.process(&[create_room_ix(&key, 2, ENTRY_FEE, 0)], &[&creator]).await.unwrap();
+
+    assert_eq!(env.room(&room).await.creator, key);
+    assert_eq!(env.lamports(&room).await, room_rent(2) + ENTRY_FEE);
+    assert_eq!(env.creator_state(&key).await.unwrap().next_room_nonce, 1);
+    assert_eq!(env.lamports(&state).await, creator_state_rent());
+}
+
+#[tokio::test]
+async fn join_room_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 2, 1).await;
+
+    let result = env.process(&[join_room_ix(&room.creator.pubkey(), &room.address)], &[&room.creator]).await;
+    assert_uno_error(result, UnoError::PlayerAlreadyJoined);
+
+    let latecomer = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[join_room_ix(&latecomer.pubkey(), &room.address)], &[&latecomer]).await;
+    assert_uno_error(result, UnoError::RoomFull);
+
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, 1).await;
+    let latecomer = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[join_room_ix(&latecomer.pubkey(), &room.address)], &[&latecomer]).await;
+    assert_uno_error(result, UnoError::RoomNotWaiting);
+}
+
+#[tokio::test]
+async fn start_game_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 2, 0).await;
+
+    let result = env.process(&[start_game_ix(&room.creator.pubkey(), &room.address, "g")], &[&room.creator]).await;
+    assert_uno_error(result, UnoError::NotEnoughPlayers);
+
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[start_game_ix(&outsider.pubkey(), &room.address, "g")], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&player.pubkey(), &room.address)], &[&player]).await.unwrap();
+
+    let result = env.process(&[start_game_ix(&player.pubkey(), &room.address, "g")], &[&player]).await;
+    assert_uno_error(result, UnoError::PlayersNotReady);
+
+    env.ready_up(&room.address, &[&room.creator, &player]).await;
+    let result = env.process(&[start_game_ix(&player.pubkey(), &room.address, "g")], &[&player]).await;
+    assert_uno_error(result, UnoError::SeedsNotRevealed);
+
+    env.reveal_seeds(&room.address, &[&room.creator, &player]).await;
+    let mut ix = start_game_ix(&player.pubkey(), &room.address, "g");
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_uno_error(result, UnoError::InvalidSlotHashes);
+
+    env.process(&[start_game_ix(&player.pubkey(), &room.address, "g")], &[&player]).await.unwrap();
+
+    let result = env.process(&[start_game_ix(&player.pubkey(), &room.address, "g2")], &[&player]).await;
+    assert_uno_error(result, UnoError::RoomNotWaiting);
+}
+
+#[tokio::test]
+async fn end_game_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let waiting = open_room(&mut env, 2, 1).await;
+    let authority = env.result_authority.insecure_clone();
+
+    let result = env
+        .process(&[end_game_ix(&authority.pubkey(), &waiting.address, waiting.creator.pubkey())], &[&authority])
+        .await;
+    assert_uno_error(result, UnoError::GameNotInProgress);
+
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 2, 1).await;
+    let authority = env.result_authority.insecure_clone();
+    let player = &room.players[0];
+
+    // Gracz nie może sam ogłosić się zwycięzcą
+    let result = env.process(&[end_game_ix(&player.pubkey(), &room.address, player.pubkey())], &[player]).await;
+    assert_uno_error(result, UnoError::ResultNotApproved);
+
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[end_game_ix(&outsider.pubkey(), &room.address, player.pubkey())], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+
+    let result = env
+        .process(&[end_game_ix(&authority.pubkey(), &room.address, outsider.pubkey())], &[&authority])
+        .await;
+    assert_uno_error(result, UnoError::WinnerNotAPlayer);
+}
+
+#[tokio::test]
+async fn end_game_accepts_unanimous_players() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 2, 1).await;
+    let player = &room.players[0];
+
+    let ix = instruction::end_game_unanimous(
+        &program_id(),
+        &player.pubkey(),
+        &room.address,
+        &player.pubkey(),
+        &[room.creator.pubkey()],
+    );
+    env.process(&[ix], &[player, &room.creator]).await.unwrap();
+
+    assert_eq!(env.room(&room.address).await.winner, Some(player.pubkey()));
+}
+
+#[tokio::test]
+async fn claim_prize_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, 2).await;
+    let fee_recipient = env.fee_recipient;
+    let winner = &room.players[0];
+    let creator = room.creator.pubkey();
+
+    let result = env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &creator)], &[winner]).await;
+    assert_uno_error(result, UnoError::GameNotCompleted);
+
+    let mut env = TestEnv::start(program_test()).await;
+    let room = completed_room(&mut env, 1).await;
+    let fee_recipient = env.fee_recipient;
+    let winner = &room.players[0];
+    let loser = &room.players[1];
+    let creator = room.creator.pubkey();
+
+    let result = env.process(&[claim_prize_ix(&loser.pubkey(), &room.address, &fee_recipient, &creator)], &[loser]).await;
+    assert_uno_error(result, UnoError::NotTheWinner);
+
+    let wrong_wallet = Pubkey::new_unique();
+    let result = env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &wrong_wallet, &creator)], &[winner]).await;
+    assert_uno_error(result, UnoError::InvalidPlatformWallet);
+
+    let result = env
+        .process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &loser.pubkey())], &[winner])
+        .await;
+    assert_uno_error(result, UnoError::NotRoomCreator);
+
+    env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &creator)], &[winner])
+        .await
+        .unwrap();
+
+    // Pokój jest zamknięty, więc drugie odebranie nagrody jest niemożliwe
+    let result = env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &creator)], &[winner]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
+
+#[tokio::test]
+async fn cancel_rejects_started_game() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 2, 1).await;
+
+    let others = [room.players[0].pubkey()];
+    let result = env.process(&[cancel_room_ix(&room.creator.pubkey(), &room.address, &others)], &[&room.creator]).await;
+    assert_uno_error(result, UnoError::RoomNotWaiting);
+}
+
+#[tokio::test]
+async fn leave_room_refunds_player_and_keeps_room_open() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 3, 2).await;
+    let leaver = &room.players[0];
+
+    env.process(&[leave_room_ix(&leaver.pubkey(), &room.address)], &[leaver]).await.unwrap();
+
+    assert_eq!(env.lamports(&leaver.pubkey()).await, STARTING_BALANCE);
+    assert_eq!(env.lamports(&room.address).await, room_rent(3) + ENTRY_FEE * 2);
+    let state = env.room(&room.address).await;
+    assert_eq!(state.players, vec![room.creator.pubkey(), room.players[1].pubkey()]);
+    assert_eq!(state.status, GameStatus::WaitingForPlayers);
+
+    // Zwolnione miejsce może zająć ktoś inny
+    let newcomer = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&newcomer.pubkey(), &room.address)], &[&newcomer]).await.unwrap();
+}
+
+#[tokio::test]
+async fn leave_room_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 3, 1).await;
+
+    let result = env.process(&[leave_room_ix(&room.creator.pubkey(), &room.address)], &[&room.creator]).await;
+    assert_uno_error(result, UnoError::CreatorCannotLeave);
+
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[leave_room_ix(&outsider.pubkey(), &room.address)], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+
+    let player = &room.players[0];
+    env.prepare_start(&room.address, &[&room.creator, player]).await;
+    let result = env.process(&[leave_room_ix(&player.pubkey(), &room.address)], &[player]).await;
+    assert_uno_error(result, UnoError::SeedRevealInProgress);
+
+    env.process(&[start_game_ix(&player.pubkey(), &room.address, "g")], &[player]).await.unwrap();
+    let result = env.process(&[leave_room_ix(&player.pubkey(), &room.address)], &[player]).await;
+    assert_uno_error(result, UnoError::RoomNotWaiting);
+}
+
+#[tokio::test]
+async fn reclaim_abandoned_refunds_after_timeout() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, 2).await;
+    let players = room.all_keys();
+    let initiator = &room.players[1];
+    let creator_before = env.lamports(&room.creator.pubkey()).await;
+
+    let result = env.process(&[reclaim_abandoned_ix(&initiator.pubkey(), &room.address, &players)], &[initiator]).await;
+    assert_uno_error(result, UnoError::GameNotAbandoned);
+
+    env.advance_clock(ABANDON_TIMEOUT_SECS).await;
+    env.process(&[reclaim_abandoned_ix(&initiator.pubkey(), &room.address, &players)], &[initiator])
+        .await
+        .unwrap();
+
+    for player in &room.players {
+        assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE);
+    }
+    assert_eq!(env.lamports(&room.creator.pubkey()).await, creator_before + ENTRY_FEE + room_rent(3));
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn reclaim_abandoned_rejections() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_room(&mut env, 2, 1).await;
+    let players = room.all_keys();
+    let player = &room.players[0];
+
+    let result = env.process(&[reclaim_abandoned_ix(&player.pubkey(), &room.address, &players)], &[player]).await;
+    assert_uno_error(result, UnoError::GameNotInProgress);
+
+    env.prepare_start(&room.address, &[&room.creator, player]).await;
+    env.process(&[start_game_ix(&player.pubkey(), &room.address, "g")], &[player]).await.unwrap();
+    env.advance_clock(ABANDON_TIMEOUT_SECS).await;
+
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[reclaim_abandoned_ix(&outsider.pubkey(), &room.address, &players)], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+
+    let reversed: Vec<_> = players.iter().rev().copied().collect();
+    let result = env.process(&[reclaim_abandoned_ix(&player.pubkey(), &room.address, &reversed)], &[player]).await;
+    assert_uno_error(result, UnoError::RefundAccountMismatch);
+}
+
+#[tokio::test]
+async fn only_upgrade_authority_initializes_config() {
+    let mut context = program_test().start_with_context().await;
+    let upgrade_authority = Keypair::new();
+    set_upgrade_authority(&mut context, &upgrade_authority.pubkey());
+    let payer = context.payer.insecure_clone();
+
+    // Pierwszy chętny nie może przejąć konfiguracji ani wskazać siebie jako serwera wyników
+    let params = config_params(payer.pubkey(), payer.pubkey());
+    let ix = initialize_config_ix(&payer.pubkey(), params.clone());
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], blockhash);
+    let result = context.banks_client.process_transaction(transaction).await;
+    assert_uno_error(result, UnoError::NotUpgradeAuthority);
+    assert!(context.banks_client.get_account(config_pda()).await.unwrap().is_none());
+
+    // Upgrade authority zakłada konfigurację i zostaje jej administratorem
+    let fund = system_instruction::transfer(&payer.pubkey(), &upgrade_authority.pubkey(), STARTING_BALANCE);
+    let ix = initialize_config_ix(&upgrade_authority.pubkey(), config_params(Pubkey::new_unique(), Pubkey::new_unique()));
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[fund, ix], Some(&payer.pubkey()), &[&payer, &upgrade_authority], blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(config_pda()).await.unwrap().expect("config account exists");
+    let (_, payload) = unpack_account_data(&account.data, &ProgramConfig::DISCRIMINATOR).unwrap();
+    assert_eq!(ProgramConfig::try_from_slice(payload).unwrap().admin, upgrade_authority.pubkey());
+}
+
+#[tokio::test]
+async fn config_admin_controls_fee_recipient() {
+    let mut env = TestEnv::start(program_test()).await;
+    let admin = env.context.payer.insecure_clone();
+    let authority = env.result_authority.pubkey();
+
+    let result = env.process(&[initialize_config_ix(&admin.pubkey(), config_params(Pubkey::new_unique(), authority))], &[]).await;
+    assert_uno_error(result, UnoError::ConfigAlreadyInitialized);
+
+    let impostor = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env
+        .process(&[update_config_ix(&impostor.pubkey(), impostor.pubkey(), config_params(impostor.pubkey(), authority))], &[&impostor])
+        .await;
+    assert_uno_error(result, UnoError::NotConfigAdmin);
+
+    let mut invalid = config_params(Pubkey::new_unique(), authority);
+    invalid.fee_basis_points = 10_001;
+    let result = env.process(&[update_config_ix(&admin.pubkey(), admin.pubkey(), invalid)], &[]).await;
+    assert_uno_error(result, UnoError::InvalidConfigParams);
+
+    let mut invalid = config_params(Pubkey::new_unique(), authority);
+    invalid.host_inactivity_secs = 0;
+    let result = env.process(&[update_config_ix(&admin.pubkey(), admin.pubkey(), invalid)], &[]).await;
+    assert_uno_error(result, UnoError::InvalidConfigParams);
+
+    // Nowy portfel platformy obowiązuje przy kolejnych wypłatach
+    let new_recipient = Pubkey::new_unique();
+    env.process(&[update_config_ix(&admin.pubkey(), admin.pubkey(), config_params(new_recipient, authority))], &[])
+        .await
+        .unwrap();
+    env.fee_recipient = new_recipient;
+
+    let room = completed_room(&mut env, 0).await;
+    let creator = room.creator.pubkey();
+    env.process(&[claim_prize_ix(&creator, &room.address, &new_recipient, &creator)], &[&room.creator])
+        .await
+        .unwrap();
+    assert_eq!(env.lamports(&new_recipient).await, ENTRY_FEE * 3 * FEE_BASIS_POINTS as u64 / 10_000);
+}
@@ -0,0 +1,185 @@
+// Testy tabel wypłat dla kilku miejsc i niezależnego odbierania udziałów
+
+mod common;
+
+use common::*;
+use solana_program::{pubkey::Pubkey, rent::Rent};
+use solana_sdk::signature::{Keypair, Signer};
+use uno_solana::{
+    error::UnoError,
+    instruction::{self, RoomOptions},
+    GameRoom,
+};
+
+struct Room {
+    players: Vec<Keypair>,
+    address: Pubkey,
+}
+
+fn payouts(payout_bps: &[u16]) -> RoomOptions {
+    RoomOptions { payout_bps: payout_bps.to_vec(), ..RoomOptions::default() }
+}
+
+async fn started_room(env: &mut TestEnv, max_players: u8, payout_bps: &[u16]) -> Room {
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let ix = instruction::create_room(&program_id(), &creator.pubkey(), max_players, ENTRY_FEE, 0, payouts(payout_bps));
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+
+    let mut players = vec![creator];
+    players.extend(join_players(env, &address, max_players as usize - 1).await);
+
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.prepare_start(&address, &signers).await;
+    env.process(&[start_game_ix(&players[0].pubkey(), &address, "g")], &[&players[0]]).await.unwrap();
+    Room { players, address }
+}
+
+#[tokio::test]
+async fn start_requires_a_player_for_every_paid_place() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let ix = instruction::create_room(&program_id(), &creator.pubkey(), 4, ENTRY_FEE, 0, payouts(&[6_000, 3_000, 1_000]));
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+
+    let second = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&second.pubkey(), &address)], &[&second]).await.unwrap();
+    env.prepare_start(&address, &[&creator, &second]).await;
+
+    // Dwóch graczy nie zajmie trzeciego płatnego miejsca - jego udział zostałby w pokoju na zawsze
+    let result = env.process(&[start_game_ix(&creator.pubkey(), &address, "g")], &[&creator]).await;
+    assert_uno_error(result, UnoError::NotEnoughPlayers);
+}
+
+fn end_game_ranked_ix(authority: &Pubkey, room: &Pubkey, placements: &[Pubkey]) -> solana_program::instruction::Instruction {
+    instruction::end_game_ranked(&program_id(), authority, room, placements, &[])
+}
+
+fn net_of_fee(share: u64) -> u64 {
+    share - share * FEE_BASIS_POINTS as u64 / 10_000
+}
+
+#[tokio::test]
+async fn placed_players_claim_their_shares_independently() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, &[7_000, 3_000]).await;
+    let keys: Vec<Pubkey> = room.players.iter().map(|p| p.pubkey()).collect();
+    let authority = env.result_authority.insecure_clone();
+
+    // Sam zwycięzca nie wystarcza przy wypłacie dla dwóch miejsc
+    let result = env.process(&[end_game_ix(&authority.pubkey(), &room.address, keys[2])], &[&authority]).await;
+    assert_uno_error(result, UnoError::FinishingOrderRequired);
+
+    let placements = [keys[2], keys[0], keys[1]];
+    env.process(&[end_game_ranked_ix(&authority.pubkey(), &room.address, &placements)], &[&authority])
+        .await
+        .unwrap();
+    let stored = env.room(&room.address).await;
+    assert_eq!(stored.placements, placements.to_vec());
+    assert_eq!(stored.winner, Some(keys[2]));
+
+    let fee_recipient = env.fee_recipient;
+    let total_prize = ENTRY_FEE * 3;
+
+    // Trzecie miejsce nie jest objęte tabelą wypłat
+    let third = &room.players[1];
+    let result = env.process(&[claim_prize_ix(&third.pubkey(), &room.address, &fee_recipient, &keys[0])], &[third]).await;
+    assert_uno_error(result, UnoError::NotTheWinner);
+
+    // Drugie miejsce odbiera 30% - pokój pozostaje otwarty dla zwycięzcy
+    let second = &room.players[0];
+    let before = env.lamports(&keys[0]).await;
+    env.process(&[claim_prize_ix(&second.pubkey(), &room.address, &fee_recipient, &keys[0])], &[second])
+        .await
+        .unwrap();
+    let second_share = total_prize * 3_000 / 10_000;
+    assert_eq!(env.lamports(&keys[0]).await, before + net_of_fee(second_share));
+    assert_eq!(env.room(&room.address).await.payouts_claimed, vec![false, true]);
+
+    let result = env.process(&[claim_prize_ix(&second.pubkey(), &room.address, &fee_recipient, &keys[0])], &[second]).await;
+    assert_uno_error(result, UnoError::PrizeAlreadyClaimed);
+
+    // Zwycięzca odbiera resztę puli, a pokój się zamyka
+    let winner = &room.players[2];
+    let winner_before = env.lamports(&keys[2]).await;
+    let creator_before = env.lamports(&keys[0]).await;
+    env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &keys[0])], &[winner])
+        .await
+        .unwrap();
+    let winner_share = total_prize - second_share;
+    assert_eq!(env.lamports(&keys[2]).await, winner_before + net_of_fee(winner_share));
+    assert_eq!(
+        env.lamports(&fee_recipient).await,
+        second_share - net_of_fee(second_share) + winner_share - net_of_fee(winner_share)
+    );
+    assert_eq!(env.lamports(&keys[0]).await, creator_before + Rent::default().minimum_balance(GameRoom::space(3)));
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn create_room_rejects_invalid_payout_tables() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+
+    for table in [&[6_000, 3_000][..], &[3_000, 7_000], &[5_000, 3_000, 2_000], &[10_000, 0]] {
+        let ix = instruction::create_room(&program_id(), &creator.pubkey(), 2, ENTRY_FEE, 0, payouts(table));
+        let result = env.process(&[ix], &[&creator]).await;
+        assert_uno_error(result, UnoError::InvalidPayoutTable);
+    }
+
+    let ix = instruction::create_room(&program_id(), &creator.pubkey(), 4, ENTRY_FEE, 0, payouts(&[6_000, 3_000, 1_000]));
+    env.process(&[ix], &[&creator]).await.unwrap();
+    assert_eq!(env.room(&room_pda(&creator.pubkey(), 0)).await.payout_bps, vec![6_000, 3_000, 1_000]);
+}
+
+#[tokio::test]
+async fn ranked_result_must_list_every_player_once() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = started_room(&mut env, 3, &[7_000, 3_000]).await;
+    let keys: Vec<Pubkey> = room.players.iter().map(|p| p.pubkey()).collect();
+    let authority = env.result_authority.insecure_clone();
+
+    for placements in [vec![keys[0], keys[1]], vec![keys[0], keys[0], keys[1]], vec![keys[0], keys[1], Pubkey::new_unique()]] {
+        let result = env.process(&[end_game_ranked_ix(&authority.pubkey(), &room.address, &placements)], &[&authority]).await;
+        assert_uno_error(result, UnoError::InvalidFinishingOrder);
+    }
+
+    // Bez serwera gry kolejność musi zatwierdzić każdy gracz
+    let player = &room.players[1];
+    let result = env.process(&[end_game_ranked_ix(&player.pubkey(), &room.address, &keys)], &[player]).await;
+    assert_uno_error(result, UnoError::ResultNotApproved);
+}
+
+#[tokio::test]
+async fn ten_player_room_pays_every_place() {
+    let mut env = TestEnv::start(program_test()).await;
+    let table = [3_000, 2_000, 1_500, 1_000, 800, 600, 500, 300, 200, 100];
+    let room = started_room(&mut env, 10, &table).await;
+    let keys: Vec<Pubkey> = room.players.iter().map(|p| p.pubkey()).collect();
+    let placements: Vec<Pubkey> = keys.iter().rev().copied().collect();
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[end_game_ranked_ix(&authority.pubkey(), &room.address, &placements)], &[&authority])
+        .await
+        .unwrap();
+
+    let fee_recipient = env.fee_recipient;
+    let total_prize = ENTRY_FEE * 10;
+    // Najpierw miejsca 2-10 - zwycięzca odbiera ostatni i zamyka pokój
+    for (place, bps) in table.iter().enumerate().skip(1) {
+        let index = keys.iter().position(|key| *key == placements[place]).unwrap();
+        let player = &room.players[index];
+        let before = env.lamports(&keys[index]).await;
+        env.process(&[claim_prize_ix(&player.pubkey(), &room.address, &fee_recipient, &keys[0])], &[player])
+            .await
+            .unwrap();
+        let share = total_prize * *bps as u64 / 10_000;
+        assert_eq!(env.lamports(&keys[index]).await, before + net_of_fee(share));
+    }
+
+    let winner = &room.players[9];
+    env.process(&[claim_prize_ix(&winner.pubkey(), &room.address, &fee_recipient, &keys[0])], &[winner])
+        .await
+        .unwrap();
+    assert!(env.account(&room.address).await.is_none());
+}
@@ -0,0 +1,77 @@
+// Testy pokojów prywatnych: lista zaproszonych graczy i hash sekretu zaproszenia
+
+mod common;
+
+use common::*;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use uno_solana::{
+    error::UnoError,
+    instruction::{self, RoomOptions},
+    invite_hash, GameRoom, RoomAccess, MAX_ALLOWLIST_LEN,
+};
+
+async fn private_room(env: &mut TestEnv, access: RoomAccess) -> (Keypair, Pubkey) {
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let options = RoomOptions { access, ..RoomOptions::default() };
+    let ix = instruction::create_room(&program_id(), &creator.pubkey(), 4, ENTRY_FEE, 0, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+    (creator, address)
+}
+
+#[tokio::test]
+async fn allowlist_room_admits_only_invited_players() {
+    let mut env = TestEnv::start(program_test()).await;
+    let invited = env.funded_keypair(STARTING_BALANCE).await;
+    let stranger = env.funded_keypair(STARTING_BALANCE).await;
+    let access = RoomAccess::Allowlist(vec![invited.pubkey(), Pubkey::new_unique()]);
+    let (creator, address) = private_room(&mut env, access.clone()).await;
+
+    let result = env.process(&[join_room_ix(&stranger.pubkey(), &address)], &[&stranger]).await;
+    assert_uno_error(result, UnoError::NotInvited);
+
+    env.process(&[join_room_ix(&invited.pubkey(), &address)], &[&invited]).await.unwrap();
+
+    let account = env.account(&address).await.unwrap();
+    assert_eq!(account.data.len(), GameRoom::space_with_access(4, &access));
+    let room = env.room(&address).await;
+    assert_eq!(room.access, access);
+    assert_eq!(room.players, vec![creator.pubkey(), invited.pubkey()]);
+}
+
+#[tokio::test]
+async fn invite_hash_room_requires_matching_secret() {
+    let mut env = TestEnv::start(program_test()).await;
+    let secret = b"friday night uno";
+    let (_, address) = private_room(&mut env, RoomAccess::InviteHash(invite_hash(secret))).await;
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+
+    let result = env.process(&[join_room_ix(&player.pubkey(), &address)], &[&player]).await;
+    assert_uno_error(result, UnoError::InvalidInviteSecret);
+
+    let ix = instruction::join_private_room(&program_id(), &player.pubkey(), &address, b"wrong secret");
+    let result = env.process(&[ix], &[&player]).await;
+    assert_uno_error(result, UnoError::InvalidInviteSecret);
+
+    let ix = instruction::join_private_room(&program_id(), &player.pubkey(), &address, secret);
+    env.process(&[ix], &[&player]).await.unwrap();
+    assert_eq!(env.lamports(&player.pubkey()).await, STARTING_BALANCE - ENTRY_FEE);
+    assert!(env.room(&address).await.players.contains(&player.pubkey()));
+}
+
+#[tokio::test]
+async fn create_room_rejects_invalid_allowlists() {
+    let mut env = TestEnv::start(program_test()).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let duplicate = Pubkey::new_unique();
+    let too_long = (0..=MAX_ALLOWLIST_LEN).map(|_| Pubkey::new_unique()).collect();
+
+    for allowed in [Vec::new(), vec![duplicate, duplicate], too_long] {
+        let access = RoomAccess::Allowlist(allowed);
+        let options = RoomOptions { access, ..RoomOptions::default() };
+        let ix = instruction::create_room(&program_id(), &creator.pubkey(), 2, ENTRY_FEE, 0, options);
+        let result = env.process(&[ix], &[&creator]).await;
+        assert_uno_error(result, UnoError::InvalidRoomAccess);
+    }
+}
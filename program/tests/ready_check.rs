@@ -0,0 +1,77 @@
+// Testy gotowości graczy przed startem gry i kworum ustalanego przez twórcę
+
+mod common;
+
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+use uno_solana::{error::UnoError, instruction};
+
+
+#[tokio::test]
+async fn roster_change_clears_ready_bits() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, players) = room_with_players(&mut env, 3, 1).await;
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.ready_up(&address, &signers).await;
+    assert_eq!(env.room(&address).await.ready, vec![true, true]);
+
+    // Nowy gracz zmienia skład - wszyscy muszą ponownie potwierdzić gotowość
+    let newcomer = env.funded_keypair(STARTING_BALANCE).await;
+    env.process(&[join_room_ix(&newcomer.pubkey(), &address)], &[&newcomer]).await.unwrap();
+    assert_eq!(env.room(&address).await.ready, vec![false; 3]);
+
+    env.process(&[set_ready_ix(&players[1], &address, true)], &[&players[1]]).await.unwrap();
+    env.process(&[leave_room_ix(&newcomer.pubkey(), &address)], &[&newcomer]).await.unwrap();
+    assert_eq!(env.room(&address).await.ready, vec![false, false]);
+
+    let result = env.process(&[set_ready_ix(&newcomer, &address, true)], &[&newcomer]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+}
+
+#[tokio::test]
+async fn start_requires_every_player_ready_by_default() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, players) = room_with_players(&mut env, 2, 1).await;
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.reveal_seeds(&address, &signers).await;
+
+    env.ready_up(&address, &signers).await;
+    env.process(&[set_ready_ix(&players[1], &address, false)], &[&players[1]]).await.unwrap();
+    let result = env.process(&[start_game_ix(&players[0].pubkey(), &address, "g")], &[&players[0]]).await;
+    assert_uno_error(result, UnoError::PlayersNotReady);
+
+    env.process(&[set_ready_ix(&players[1], &address, true)], &[&players[1]]).await.unwrap();
+    env.process(&[start_game_ix(&players[0].pubkey(), &address, "g")], &[&players[0]]).await.unwrap();
+
+    let result = env.process(&[set_ready_ix(&players[1], &address, false)], &[&players[1]]).await;
+    assert_uno_error(result, UnoError::RoomNotWaiting);
+}
+
+#[tokio::test]
+async fn creator_quorum_lets_ready_players_start() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, players) = room_with_players(&mut env, 4, 2).await;
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.reveal_seeds(&address, &signers).await;
+    let creator = &players[0];
+
+    let ix = instruction::set_ready_quorum(&program_id(), &players[1].pubkey(), &address, 2);
+    let result = env.process(&[ix], &[&players[1]]).await;
+    assert_uno_error(result, UnoError::NotRoomHost);
+
+    for quorum in [1, 5] {
+        let ix = instruction::set_ready_quorum(&program_id(), &creator.pubkey(), &address, quorum);
+        let result = env.process(&[ix], &[creator]).await;
+        assert_uno_error(result, UnoError::InvalidReadyQuorum);
+    }
+
+    let ix = instruction::set_ready_quorum(&program_id(), &creator.pubkey(), &address, 2);
+    env.process(&[ix], &[creator]).await.unwrap();
+    env.ready_up(&address, &signers[..1]).await;
+    let result = env.process(&[start_game_ix(&creator.pubkey(), &address, "g")], &[creator]).await;
+    assert_uno_error(result, UnoError::PlayersNotReady);
+
+    env.ready_up(&address, &signers[2..]).await;
+    env.process(&[start_game_ix(&creator.pubkey(), &address, "g")], &[creator]).await.unwrap();
+    assert_eq!(env.room(&address).await.ready, vec![true, false, true]);
+}
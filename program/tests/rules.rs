@@ -0,0 +1,271 @@
+// Testy silnika zasad UNO: talia, kolejka, kary za dobieranie i wygrana
+
+use uno_solana::rules::{
+    decode_moves, encode_moves, replay, shuffled_deck, standard_deck, Card, Color, Direction, GameState, Move,
+    PlayOutcome, RuleError, Value, DECK_SIZE, INITIAL_HAND_SIZE,
+};
+
+const RED_5: Card = Card::new(Color::Red, Value::Number(5));
+const BLUE_5: Card = Card::new(Color::Blue, Value::Number(5));
+const RED_7: Card = Card::new(Color::Red, Value::Number(7));
+const GREEN_1: Card = Card::new(Color::Green, Value::Number(1));
+const WILD: Card = Card::new(Color::Black, Value::Wild);
+
+/// Stan gry z zadanymi rękami i kartą na stosie, bez rozdawania z talii
+fn game(hands: Vec<Vec<Card>>, top_card: Card, draw_pile: Vec<Card>) -> GameState {
+    GameState {
+        hands,
+        draw_pile,
+        top_card,
+        current_player: 0,
+        direction: Direction::Clockwise,
+        winner: None,
+    }
+}
+
+#[test]
+fn standard_deck_has_108_cards() {
+    let deck = standard_deck();
+    assert_eq!(deck.len(), DECK_SIZE);
+
+    let count = |card: Card| deck.iter().filter(|c| **c == card).count();
+    assert_eq!(count(Card::new(Color::Red, Value::Number(0))), 1);
+    assert_eq!(count(Card::new(Color::Yellow, Value::Number(9))), 2);
+    assert_eq!(count(Card::new(Color::Green, Value::Draw2)), 2);
+    assert_eq!(count(WILD), 4);
+    assert_eq!(count(Card::new(Color::Black, Value::Wild4)), 4);
+}
+
+#[test]
+fn new_game_deals_hands_like_sequential_draws() {
+    let deck = standard_deck();
+    let state = GameState::new(3, deck.clone()).unwrap();
+
+    let mut expected = deck;
+    for hand in &state.hands {
+        assert_eq!(hand.len(), INITIAL_HAND_SIZE);
+        for card in hand {
+            assert_eq!(Some(*card), expected.pop());
+        }
+    }
+    assert_eq!(Some(state.top_card), expected.pop());
+    assert_eq!(state.draw_pile, expected);
+
+    assert_eq!(GameState::new(1, standard_deck()), Err(RuleError::InvalidPlayerCount));
+    assert_eq!(GameState::new(2, standard_deck()[..14].to_vec()), Err(RuleError::NotEnoughCards));
+}
+
+#[test]
+fn cards_match_by_color_or_value_and_wilds_always_play() {
+    assert!(RED_7.can_play_on(&RED_5));
+    assert!(BLUE_5.can_play_on(&RED_5));
+    assert!(!GREEN_1.can_play_on(&RED_5));
+    assert!(WILD.can_play_on(&RED_5));
+    // Wild z wybranym kolorem przyjmuje tylko ten kolor
+    assert!(!GREEN_1.can_play_on(&Card::new(Color::Red, Value::Wild)));
+    // Na Wild odkryty na starcie (bez koloru) pasuje tylko dzika karta
+    assert!(!GREEN_1.can_play_on(&WILD));
+    assert!(WILD.can_play_on(&WILD));
+}
+
+#[test]
+fn wild_first_card_accepts_only_wilds() {
+    let wild4 = Card::new(Color::Black, Value::Wild4);
+    let mut state = game(vec![vec![RED_7, wild4, RED_7], vec![RED_7, RED_7]], WILD, vec![]);
+
+    assert_eq!(state.play_card(0, 0, None), Err(RuleError::IllegalMove));
+    assert_eq!(state.play_card(0, 1, Some(Color::Red)), Ok(PlayOutcome::Continue { next_player: 0 }));
+    assert_eq!(state.top_card, Card::new(Color::Red, Value::Wild4));
+}
+
+#[test]
+fn play_rejects_out_of_turn_and_illegal_moves() {
+    let mut state = game(vec![vec![GREEN_1, RED_7], vec![RED_7, RED_7]], RED_5, vec![]);
+
+    assert_eq!(state.play_card(1, 0, None), Err(RuleError::NotYourTurn));
+    assert_eq!(state.play_card(2, 0, None), Err(RuleError::InvalidPlayer));
+    assert_eq!(state.play_card(0, 5, None), Err(RuleError::InvalidCardIndex));
+    assert_eq!(state.play_card(0, 0, None), Err(RuleError::IllegalMove));
+
+    assert_eq!(state.play_card(0, 1, None), Ok(PlayOutcome::Continue { next_player: 1 }));
+    assert_eq!(state.top_card, RED_7);
+    assert_eq!(state.hands[0], vec![GREEN_1]);
+}
+
+#[test]
+fn wild_requires_playable_color() {
+    let mut state = game(vec![vec![WILD, RED_7], vec![RED_7]], RED_5, vec![]);
+
+    assert_eq!(state.play_card(0, 0, None), Err(RuleError::InvalidColorChoice));
+    assert_eq!(state.play_card(0, 0, Some(Color::Black)), Err(RuleError::InvalidColorChoice));
+    assert_eq!(state.hands[0].len(), 2);
+
+    state.play_card(0, 0, Some(Color::Green)).unwrap();
+    assert_eq!(state.top_card, Card::new(Color::Green, Value::Wild));
+    assert_eq!(state.current_player, 1);
+}
+
+#[test]
+fn skip_and_reverse_change_turn_order() {
+    let skip = Card::new(Color::Red, Value::Skip);
+    let reverse = Card::new(Color::Red, Value::Reverse);
+    let hands = vec![vec![skip, reverse, RED_7], vec![RED_7, RED_7], vec![RED_7, RED_7], vec![RED_7, RED_7]];
+
+    let mut state = game(hands.clone(), RED_5, vec![]);
+    assert_eq!(state.play_card(0, 0, None), Ok(PlayOutcome::Continue { next_player: 2 }));
+
+    let mut state = game(hands, RED_5, vec![]);
+    assert_eq!(state.play_card(0, 1, None), Ok(PlayOutcome::Continue { next_player: 3 }));
+    assert_eq!(state.direction, Direction::CounterClockwise);
+    state.play_card(3, 0, None).unwrap();
+    assert_eq!(state.current_player, 2);
+
+    // Dla dwóch graczy Reverse też przekazuje kolej przeciwnikowi
+    let mut state = game(vec![vec![reverse, RED_7], vec![RED_7]], RED_5, vec![]);
+    assert_eq!(state.play_card(0, 0, None), Ok(PlayOutcome::Continue { next_player: 1 }));
+    assert_eq!(state.direction, Direction::CounterClockwise);
+}
+
+// Skrypt rozgrywający jedno zagranie na serwerze gry: gracz 0 kładzie czerwoną kartę
+// o podanej wartości na czerwoną piątkę, a skrypt wypisuje kolejnego gracza i kierunek
+const SERVER_PLAY_SCRIPT: &str = r#"
+const [gamePath, count, value] = process.argv.slice(1);
+const UnoGame = require(gamePath);
+console.log = () => {};
+const players = Array.from({ length: Number(count) }, (_, i) => `p${i}`);
+const game = new UnoGame('cross-check', players);
+for (const player of players) {
+  game.playerHands[player] = [{ color: 'red', value: '7' }, { color: 'red', value: '7' }];
+}
+game.playerHands.p0.unshift({ color: 'red', value });
+game.currentCard = { color: 'red', value: '5' };
+game.playCard('p0', 0);
+process.stdout.write(`${game.currentPlayerIndex} ${game.direction}`);
+"#;
+
+/// Kolejny gracz i kierunek (1 lub -1) po zagraniu na serwerze gry
+fn server_turn_after(player_count: usize, value: &str) -> (usize, i8) {
+    let game_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../server/game/UnoGame.js");
+    let output = std::process::Command::new("node")
+        .arg("-e")
+        .arg(SERVER_PLAY_SCRIPT)
+        .arg(game_path)
+        .arg(player_count.to_string())
+        .arg(value)
+        .output()
+        .expect("node is required to run the game server script");
+    assert!(output.status.success(), "server script failed: {}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (player, direction) = stdout.split_once(' ').unwrap();
+    (player.parse().unwrap(), direction.parse().unwrap())
+}
+
+#[test]
+#[ignore = "wymaga Node.js - uruchamiane przez `cargo test -- --ignored`"]
+fn turn_order_matches_game_server() {
+    let cases = [
+        (2, Value::Reverse, "Reverse"),
+        (3, Value::Reverse, "Reverse"),
+        (2, Value::Skip, "Skip"),
+        (4, Value::Skip, "Skip"),
+        (3, Value::Draw2, "Draw2"),
+        (3, Value::Number(7), "7"),
+    ];
+
+    for (player_count, value, server_value) in cases {
+        let server = server_turn_after(player_count, server_value);
+
+        let mut hands = vec![vec![RED_7, RED_7]; player_count];
+        hands[0].insert(0, Card::new(Color::Red, value));
+        let mut state = game(hands, RED_5, vec![]);
+        state.play_card(0, 0, None).unwrap();
+        let direction = match state.direction {
+            Direction::Clockwise => 1,
+            Direction::CounterClockwise => -1,
+        };
+
+        assert_eq!((state.current_player, direction), server, "{:?} with {} players", value, player_count);
+    }
+}
+
+#[test]
+fn draw_penalties_go_to_next_player_who_loses_turn() {
+    let draw2 = Card::new(Color::Red, Value::Draw2);
+    let wild4 = Card::new(Color::Black, Value::Wild4);
+    let pile = vec![GREEN_1; 6];
+    let mut state = game(vec![vec![draw2, wild4, RED_7], vec![RED_7], vec![RED_7, RED_7]], RED_5, pile);
+
+    assert_eq!(state.play_card(0, 0, None), Ok(PlayOutcome::Continue { next_player: 2 }));
+    assert_eq!(state.hands[1].len(), 3);
+
+    state.current_player = 0;
+    state.play_card(0, 0, Some(Color::Blue)).unwrap();
+    assert_eq!(state.current_player, 2);
+    // W talii zostały tylko 4 karty - kara kończy się na pustej talii
+    assert_eq!(state.hands[1].len(), 7);
+    assert!(state.draw_pile.is_empty());
+}
+
+#[test]
+fn draw_card_passes_turn_and_fails_on_empty_deck() {
+    let mut state = game(vec![vec![GREEN_1], vec![RED_7]], RED_5, vec![BLUE_5]);
+
+    assert_eq!(state.draw_card(0), Ok(BLUE_5));
+    assert_eq!(state.hands[0], vec![GREEN_1, BLUE_5]);
+    assert_eq!(state.current_player, 1);
+    assert_eq!(state.draw_card(1), Err(RuleError::DeckEmpty));
+}
+
+#[test]
+fn last_card_wins_and_ends_game() {
+    let skip = Card::new(Color::Red, Value::Skip);
+    let mut state = game(vec![vec![skip], vec![RED_7]], RED_5, vec![GREEN_1]);
+
+    assert_eq!(state.play_card(0, 0, None), Ok(PlayOutcome::Won { winner: 0 }));
+    assert_eq!(state.winner, Some(0));
+    assert_eq!(state.play_card(1, 0, None), Err(RuleError::GameOver));
+    assert_eq!(state.draw_card(1), Err(RuleError::GameOver));
+}
+
+#[test]
+fn shuffle_is_deterministic_permutation_of_deck() {
+    let deck = shuffled_deck(&[1u8; 32]);
+    assert_eq!(deck, shuffled_deck(&[1u8; 32]));
+    assert_ne!(deck, shuffled_deck(&[2u8; 32]));
+    assert_ne!(deck, standard_deck());
+
+    let mut sorted = deck;
+    let mut expected = standard_deck();
+    let key = |card: &Card| format!("{:?}", card);
+    sorted.sort_by_key(key);
+    expected.sort_by_key(key);
+    assert_eq!(sorted, expected);
+}
+
+#[test]
+fn moves_round_trip_through_transcript_encoding() {
+    let moves = [
+        Move::Draw,
+        Move::Play { card_index: 3, chosen_color: None },
+        Move::Play { card_index: 0, chosen_color: Some(Color::Yellow) },
+    ];
+    let encoded = encode_moves(&moves);
+    assert_eq!(encoded.len(), moves.len() * Move::ENCODED_SIZE);
+    assert_eq!(decode_moves(&encoded).unwrap(), moves);
+
+    assert_eq!(decode_moves(&[1]), Err(RuleError::InvalidMoveEncoding));
+    assert_eq!(decode_moves(&[9, 0]), Err(RuleError::InvalidMoveEncoding));
+    assert_eq!(decode_moves(&[0, 4]), Err(RuleError::InvalidMoveEncoding));
+}
+
+#[test]
+fn replay_applies_moves_for_current_player() {
+    let seed = [5u8; 32];
+    let state = replay(2, &seed, &[Move::Draw, Move::Draw]).unwrap();
+
+    assert_eq!(state.hands[0].len(), INITIAL_HAND_SIZE + 1);
+    assert_eq!(state.hands[1].len(), INITIAL_HAND_SIZE + 1);
+    assert_eq!(state.current_player, 0);
+    assert_eq!(replay(2, &seed, &[Move::Play { card_index: 9, chosen_color: None }]), Err(RuleError::InvalidCardIndex));
+}
@@ -0,0 +1,91 @@
+// Testy fazy commit-reveal ziaren talii przed rozpoczęciem gry
+
+mod common;
+
+use common::*;
+use solana_program::hash::hashv;
+use solana_sdk::signature::{Keypair, Signer};
+use uno_solana::error::UnoError;
+
+#[tokio::test]
+async fn deck_seed_combines_every_revealed_seed() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, players) = room_with_players(&mut env, 3, 2).await;
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.reveal_seeds(&address, &signers).await;
+
+    let room = env.room(&address).await;
+    assert_eq!(room.seeds_revealed, vec![true; 3]);
+    let mut expected = [0u8; 32];
+    for player in &players {
+        for (byte, seed_byte) in expected.iter_mut().zip(test_seed(&player.pubkey())) {
+            *byte ^= seed_byte;
+        }
+    }
+    assert_eq!(room.seed_accumulator, expected);
+
+    // Ziarno talii = hash(ziarna graczy, hash slotu, adres pokoju) - każdy może je sprawdzić
+    env.ready_up(&address, &signers).await;
+    env.process(&[start_game_ix(&players[0].pubkey(), &address, "g")], &[&players[0]]).await.unwrap();
+    let room = env.room(&address).await;
+    let slot_hashes = env.account(&solana_program::sysvar::slot_hashes::ID).await.unwrap();
+    // Od transakcji mogły dojść nowe sloty, więc szukamy hasha wśród wszystkich wpisów sysvaru
+    let matches_some_slot = slot_hashes.data[8..]
+        .chunks_exact(8 + 32)
+        .any(|entry| hashv(&[&expected, &entry[8..], address.as_ref()]).to_bytes() == room.deck_seed);
+    assert!(matches_some_slot);
+}
+
+#[tokio::test]
+async fn reveal_requires_all_commitments_and_matching_seed() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, players) = room_with_players(&mut env, 2, 1).await;
+    let [first, second] = [&players[0], &players[1]];
+    let first_seed = test_seed(&first.pubkey());
+
+    env.process(&[commit_seed_ix(&first.pubkey(), &address, &first_seed)], &[first]).await.unwrap();
+    let result = env.process(&[commit_seed_ix(&first.pubkey(), &address, &[1u8; 32])], &[first]).await;
+    assert_uno_error(result, UnoError::SeedAlreadyCommitted);
+
+    let result = env.process(&[reveal_seed_ix(&first.pubkey(), &address, &first_seed)], &[first]).await;
+    assert_uno_error(result, UnoError::SeedsNotCommitted);
+
+    let second_seed = test_seed(&second.pubkey());
+    env.process(&[commit_seed_ix(&second.pubkey(), &address, &second_seed)], &[second]).await.unwrap();
+
+    let result = env.process(&[reveal_seed_ix(&first.pubkey(), &address, &[9u8; 32])], &[first]).await;
+    assert_uno_error(result, UnoError::InvalidSeedReveal);
+
+    // Cudze ziarno nie pasuje do zobowiązania, bo jest powiązane z kluczem gracza
+    let result = env.process(&[reveal_seed_ix(&second.pubkey(), &address, &first_seed)], &[second]).await;
+    assert_uno_error(result, UnoError::InvalidSeedReveal);
+
+    env.process(&[reveal_seed_ix(&first.pubkey(), &address, &first_seed)], &[first]).await.unwrap();
+    let result = env.process(&[reveal_seed_ix(&first.pubkey(), &address, &first_seed)], &[first]).await;
+    assert_uno_error(result, UnoError::SeedAlreadyRevealed);
+
+    let outsider = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[commit_seed_ix(&outsider.pubkey(), &address, &[1u8; 32])], &[&outsider]).await;
+    assert_uno_error(result, UnoError::NotAPlayer);
+}
+
+#[tokio::test]
+async fn room_is_frozen_once_reveal_starts() {
+    let mut env = TestEnv::start(program_test()).await;
+    let (address, players) = room_with_players(&mut env, 3, 1).await;
+    for player in &players {
+        let seed = test_seed(&player.pubkey());
+        env.process(&[commit_seed_ix(&player.pubkey(), &address, &seed)], &[player]).await.unwrap();
+    }
+
+    // Same zobowiązania nie blokują składu pokoju
+    assert!(!env.room(&address).await.seed_reveal_started());
+    let leaver = &players[1];
+    env.process(&[leave_room_ix(&leaver.pubkey(), &address)], &[leaver]).await.unwrap();
+    let seed = test_seed(&players[0].pubkey());
+    env.process(&[reveal_seed_ix(&players[0].pubkey(), &address, &seed)], &[&players[0]]).await.unwrap();
+
+    let latecomer = env.funded_keypair(STARTING_BALANCE).await;
+    let result = env.process(&[join_room_ix(&latecomer.pubkey(), &address)], &[&latecomer]).await;
+    assert_uno_error(result, UnoError::SeedRevealInProgress);
+}
@@ -0,0 +1,412 @@
+// Testy pokojów z wpisowym w tokenie SPL: skarbiec PDA, wypłata nagrody, zwroty
+
+mod common;
+
+use common::*;
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+use spl_token::state::{Account as TokenAccount, Mint};
+use uno_solana::{
+    error::UnoError,
+    instruction::{self, RoomOptions},
+    RoomAccess,
+};
+
+const TOKEN_FEE: u64 = 5_000_000;
+const STARTING_TOKENS: u64 = 100_000_000;
+
+struct TokenRoom {
+    mint: Pubkey,
+    players: Vec<Keypair>,
+    address: Pubkey,
+}
+
+impl TokenRoom {
+    fn token_account(&self, index: usize) -> Pubkey {
+        get_associated_token_address(&self.players[index].pubkey(), &self.mint)
+    }
+
+    fn token_accounts(&self) -> Vec<Pubkey> {
+        (0..self.players.len()).map(|i| self.token_account(i)).collect()
+    }
+}
+
+async fn create_mint(env: &mut TestEnv) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = env.context.payer.pubkey();
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let ixs = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(&spl_token::ID, &mint.pubkey(), &payer, None, 6).unwrap(),
+    ];
+    env.process(&ixs, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Zakłada ATA portfela i (opcjonalnie) zasila je tokenami - mintem zarządza płatnik testów
+async fn fund_tokens(env: &mut TestEnv, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+    let payer = env.context.payer.pubkey();
+    let ata = get_associated_token_address(owner, mint);
+    let mut ixs = vec![create_associated_token_account(&payer, owner, mint, &spl_token::ID)];
+    if amount > 0 {
+        ixs.push(spl_token::instruction::mint_to(&spl_token::ID, mint, &ata, &payer, &[], amount).unwrap());
+    }
+    env.process(&ixs, &[]).await.unwrap();
+    ata
+}
+
+
+async fn token_balance(env: &mut TestEnv, account: &Pubkey) -> u64 {
+    let account = env.account(account).await.expect("token account exists");
+    TokenAccount::unpack(&account.data).unwrap().amount
+}
+
+async fn open_token_room(env: &mut TestEnv, max_players: u8, other_players: usize) -> TokenRoom {
+    let mint = create_mint(env).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let creator_ata = fund_tokens(env, &creator.pubkey(), &mint, STARTING_TOKENS).await;
+    let options = RoomOptions::default();
+    let ix = instruction::create_token_room(&program_id(), &creator.pubkey(), max_players, TOKEN_FEE, 0, &mint, &creator_ata, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+
+    let mut players = vec![creator];
+    for _ in 0..other_players {
+        let player = env.funded_keypair(STARTING_BALANCE).await;
+        let player_ata = fund_tokens(env, &player.pubkey(), &mint, STARTING_TOKENS).await;
+        let ix = instruction::join_token_room(&program_id(), &player.pubkey(), &address, &player_ata);
+        env.process(&[ix], &[&player]).await.unwrap();
+        players.push(player);
+    }
+    TokenRoom { mint, players, address }
+}
+
+async fn start_token_game(env: &mut TestEnv, room: &TokenRoom) {
+    let signers: Vec<&Keypair> = room.players.iter().collect();
+    env.prepare_start(&room.address, &signers).await;
+    let creator = &room.players[0];
+    env.process(&[start_game_ix(&creator.pubkey(), &room.address, "g")], &[creator]).await.unwrap();
+}
+
+#[tokio::test]
+async fn token_game_pays_winner_and_treasury() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 3, 2).await;
+    let vault = instruction::vault_address(&program_id(), &room.address);
+    assert_eq!(token_balance(&mut env, &vault).await, TOKEN_FEE * 3);
+    assert_eq!(token_balance(&mut env, &room.token_account(1)).await, STARTING_TOKENS - TOKEN_FEE);
+
+    let fee_recipient = env.fee_recipient;
+    let treasury = fund_tokens(&mut env, &fee_recipient, &room.mint, 0).await;
+
+    start_token_game(&mut env, &room).await;
+    let winner = &room.players[2];
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[end_game_ix(&authority.pubkey(), &room.address, winner.pubkey())], &[&authority])
+        .await
+        .unwrap();
+
+    let creator = room.players[0].pubkey();
+    let creator_lamports = env.lamports(&creator).await;
+    let vault_rent = env.lamports(&vault).await;
+    let ix = instruction::claim_token_prize(
+        &program_id(),
+        &winner.pubkey(),
+        &room.address,
+        &creator,
+        &fee_recipient,
+        &room.mint,
+        &room.token_account(2),
+    );
+    env.process(&[ix], &[winner]).await.unwrap();
+
+    let total_prize = TOKEN_FEE * 3;
+    let platform_fee = total_prize * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(token_balance(&mut env, &treasury).await, platform_fee);
+    assert_eq!(
+        token_balance(&mut env, &room.token_account(2)).await,
+        STARTING_TOKENS - TOKEN_FEE + total_prize - platform_fee
+    );
+    // Skarbiec i pokój są zamknięte, czynsz obu kont wraca do twórcy
+    assert!(env.account(&vault).await.is_none());
+    assert!(env.account(&room.address).await.is_none());
+    assert_eq!(env.lamports(&creator).await, creator_lamports + vault_rent + room_rent(3));
+}
+
+#[tokio::test]
+async fn placed_player_claims_after_creator_closes_token_account() {
+    let mut env = TestEnv::start(program_test()).await;
+    let mint = create_mint(&mut env).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let creator_ata = fund_tokens(&mut env, &creator.pubkey(), &mint, TOKEN_FEE).await;
+    let options = RoomOptions { payout_bps: vec![7_000, 3_000], ..RoomOptions::default() };
+    let ix = instruction::create_token_room(&program_id(), &creator.pubkey(), 2, TOKEN_FEE, 0, &mint, &creator_ata, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+    let address = room_pda(&creator.pubkey(), 0);
+
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+    let player_ata = fund_tokens(&mut env, &player.pubkey(), &mint, STARTING_TOKENS).await;
+    let ix = instruction::join_token_room(&program_id(), &player.pubkey(), &address, &player_ata);
+    env.process(&[ix], &[&player]).await.unwrap();
+    let room = TokenRoom { mint, players: vec![creator, player], address };
+    let fee_recipient = env.fee_recipient;
+    fund_tokens(&mut env, &fee_recipient, &mint, 0).await;
+
+    start_token_game(&mut env, &room).await;
+    let (creator, player) = (&room.players[0], &room.players[1]);
+    let authority = env.result_authority.insecure_clone();
+    let ix = instruction::end_game_ranked(&program_id(), &authority.pubkey(), &address, &[creator.pubkey(), player.pubkey()], &[]);
+    env.process(&[ix], &[&authority]).await.unwrap();
+
+    // Twórca zamyka puste konto tokenowe - drugie miejsce i tak odbiera swój udział
+    let ix = spl_token::instruction::close_account(&spl_token::ID, &creator_ata, &creator.pubkey(), &creator.pubkey(), &[])
+        .unwrap();
+    env.process(&[ix], &[creator]).await.unwrap();
+
+    let ix = instruction::claim_token_prize(&program_id(), &player.pubkey(), &address, &creator.pubkey(), &fee_recipient, &mint, &player_ata);
+    env.process(&[ix], &[player]).await.unwrap();
+
+    let share = 2 * TOKEN_FEE * 3_000 / 10_000;
+    let platform_fee = share * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(token_balance(&mut env, &player_ata).await, STARTING_TOKENS - TOKEN_FEE + share - platform_fee);
+}
+
+/// Dowolny posiadacz tokenów przesyła je na skarbiec pokoju
+async fn donate_to_vault(env: &mut TestEnv, room: &TokenRoom, amount: u64) {
+    let donor = env.funded_keypair(STARTING_BALANCE).await;
+    let donor_ata = fund_tokens(env, &donor.pubkey(), &room.mint, amount).await;
+    let vault = instruction::vault_address(&program_id(), &room.address);
+    let ix = spl_token::instruction::transfer(&spl_token::ID, &donor_ata, &vault, &donor.pubkey(), &[], amount).unwrap();
+    env.process(&[ix], &[&donor]).await.unwrap();
+}
+
+#[tokio::test]
+async fn donated_tokens_do_not_block_final_claim() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 2, 1).await;
+    let fee_recipient = env.fee_recipient;
+    fund_tokens(&mut env, &fee_recipient, &room.mint, 0).await;
+    start_token_game(&mut env, &room).await;
+    let winner = &room.players[1];
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[end_game_ix(&authority.pubkey(), &room.address, winner.pubkey())], &[&authority])
+        .await
+        .unwrap();
+
+    donate_to_vault(&mut env, &room, 1).await;
+
+    let creator = room.players[0].pubkey();
+    let ix = instruction::claim_token_prize(
+        &program_id(),
+        &winner.pubkey(),
+        &room.address,
+        &creator,
+        &fee_recipient,
+        &room.mint,
+        &room.token_account(1),
+    );
+    env.process(&[ix], &[winner]).await.unwrap();
+
+    // Podarowany token trafia do twórcy, a skarbiec i pokój są zamknięte
+    assert_eq!(token_balance(&mut env, &room.token_account(0)).await, STARTING_TOKENS - TOKEN_FEE + 1);
+    assert!(env.account(&instruction::vault_address(&program_id(), &room.address)).await.is_none());
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn donated_tokens_do_not_block_cancel() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 3, 1).await;
+    let creator = &room.players[0];
+    donate_to_vault(&mut env, &room, 1).await;
+
+    let ix = instruction::cancel_token_room(&program_id(), &creator.pubkey(), &creator.pubkey(), &room.address, &room.token_accounts());
+    env.process(&[ix], &[creator]).await.unwrap();
+
+    assert_eq!(token_balance(&mut env, &room.token_account(0)).await, STARTING_TOKENS + 1);
+    assert_eq!(token_balance(&mut env, &room.token_account(1)).await, STARTING_TOKENS);
+    assert!(env.account(&instruction::vault_address(&program_id(), &room.address)).await.is_none());
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn prefunded_vault_does_not_block_token_room_creation() {
+    let mut env = TestEnv::start(program_test()).await;
+    let mint = create_mint(&mut env).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let creator_ata = fund_tokens(&mut env, &creator.pubkey(), &mint, STARTING_TOKENS).await;
+    let address = room_pda(&creator.pubkey(), 0);
+    let vault = instruction::vault_address(&program_id(), &address);
+
+    let griefer = env.funded_keypair(STARTING_BALANCE).await;
+    let ix = system_instruction::transfer(&griefer.pubkey(), &vault, Rent::default().minimum_balance(0));
+    env.process(&[ix], &[&griefer]).await.unwrap();
+
+    let options = RoomOptions::default();
+
+    let ix = instruction::create_token_room(&program_id(), &creator.pubkey(), 2, TOKEN_FEE, 0, &mint, &creator_ata, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+
+    assert_eq!(token_balance(&mut env, &vault).await, TOKEN_FEE);
+    assert_eq!(env.lamports(&vault).await, Rent::default().minimum_balance(TokenAccount::LEN));
+}
+
+#[tokio::test]
+async fn token_room_accepts_room_options() {
+    let mut env = TestEnv::start(program_test()).await;
+    let mint = create_mint(&mut env).await;
+    let creator = env.funded_keypair(STARTING_BALANCE).await;
+    let creator_ata = fund_tokens(&mut env, &creator.pubkey(), &mint, STARTING_TOKENS).await;
+    let invited = Pubkey::new_unique();
+    let options = RoomOptions {
+        payout_bps: vec![7_000, 3_000],
+        access: RoomAccess::Allowlist(vec![invited]),
+        auto_start: true,
+    };
+
+    let ix = instruction::create_token_room(&program_id(), &creator.pubkey(), 3, TOKEN_FEE, 0, &mint, &creator_ata, options);
+    env.process(&[ix], &[&creator]).await.unwrap();
+
+    let room = env.room(&room_pda(&creator.pubkey(), 0)).await;
+    assert_eq!(room.mint, Some(mint));
+    assert_eq!(room.payout_bps, vec![7_000, 3_000]);
+    assert_eq!(room.access, RoomAccess::Allowlist(vec![invited]));
+    assert!(room.auto_start);
+}
+
+#[tokio::test]
+async fn cancel_token_room_refunds_everyone() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 3, 2).await;
+    let creator = &room.players[0];
+
+    // Konta w złej kolejności są odrzucane
+    let mut swapped = room.token_accounts();
+    swapped.swap(1, 2);
+    let ix = instruction::cancel_token_room(&program_id(), &creator.pubkey(), &creator.pubkey(), &room.address, &swapped);
+    let result = env.process(&[ix], &[creator]).await;
+    assert_uno_error(result, UnoError::RefundAccountMismatch);
+
+    let ix = instruction::cancel_token_room(&program_id(), &creator.pubkey(), &creator.pubkey(), &room.address, &room.token_accounts());
+    env.process(&[ix], &[creator]).await.unwrap();
+
+    for account in room.token_accounts() {
+        assert_eq!(token_balance(&mut env, &account).await, STARTING_TOKENS);
+    }
+    assert!(env.account(&instruction::vault_address(&program_id(), &room.address)).await.is_none());
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn leave_token_room_refunds_player() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 3, 1).await;
+    let player = &room.players[1];
+
+    let ix = instruction::leave_token_room(&program_id(), &player.pubkey(), &room.address, &room.token_account(1));
+    env.process(&[ix], &[player]).await.unwrap();
+
+    assert_eq!(token_balance(&mut env, &room.token_account(1)).await, STARTING_TOKENS);
+    let vault = instruction::vault_address(&program_id(), &room.address);
+    assert_eq!(token_balance(&mut env, &vault).await, TOKEN_FEE);
+    assert_eq!(env.room(&room.address).await.players, vec![room.players[0].pubkey()]);
+}
+
+#[tokio::test]
+async fn kicked_token_player_is_refunded() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 3, 1).await;
+    let creator = &room.players[0];
+    let kicked = room.players[1].pubkey();
+
+    let ix = instruction::kick_token_player(&program_id(), &creator.pubkey(), &room.address, &kicked, false, &room.token_account(1));
+    env.process(&[ix], &[creator]).await.unwrap();
+
+    assert_eq!(token_balance(&mut env, &room.token_account(1)).await, STARTING_TOKENS);
+    assert_eq!(env.room(&room.address).await.players, vec![creator.pubkey()]);
+}
+
+#[tokio::test]
+async fn reclaim_abandoned_token_game_refunds_everyone() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 2, 1).await;
+    start_token_game(&mut env, &room).await;
+    env.advance_clock(ABANDON_TIMEOUT_SECS).await;
+
+    let initiator = &room.players[1];
+    let ix = instruction::reclaim_abandoned_tokens(
+        &program_id(),
+        &initiator.pubkey(),
+        &room.address,
+        &room.players[0].pubkey(),
+        &room.token_accounts(),
+    );
+    env.process(&[ix], &[initiator]).await.unwrap();
+
+    for account in room.token_accounts() {
+        assert_eq!(token_balance(&mut env, &account).await, STARTING_TOKENS);
+    }
+    assert!(env.account(&room.address).await.is_none());
+}
+
+#[tokio::test]
+async fn token_room_rejects_foreign_accounts() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 3, 1).await;
+
+    // Dołączenie z cudzego konta tokenowego
+    let player = env.funded_keypair(STARTING_BALANCE).await;
+    fund_tokens(&mut env, &player.pubkey(), &room.mint, STARTING_TOKENS).await;
+    let ix = instruction::join_token_room(&program_id(), &player.pubkey(), &room.address, &room.token_account(1));
+    let result = env.process(&[ix], &[&player]).await;
+    assert_uno_error(result, UnoError::InvalidTokenAccount);
+
+    // Podstawiony skarbiec
+    let player_ata = get_associated_token_address(&player.pubkey(), &room.mint);
+    let mut ix = instruction::join_token_room(&program_id(), &player.pubkey(), &room.address, &player_ata);
+    ix.accounts[4].pubkey = player_ata;
+    let result = env.process(&[ix], &[&player]).await;
+    assert_uno_error(result, UnoError::InvalidVault);
+
+    // Zły program tokenów
+    let mut ix = instruction::join_token_room(&program_id(), &player.pubkey(), &room.address, &player_ata);
+    ix.accounts[5].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_uno_error(result, UnoError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn token_claim_rejects_wrong_treasury_account() {
+    let mut env = TestEnv::start(program_test()).await;
+    let room = open_token_room(&mut env, 2, 1).await;
+    start_token_game(&mut env, &room).await;
+    let winner = &room.players[1];
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[end_game_ix(&authority.pubkey(), &room.address, winner.pubkey())], &[&authority])
+        .await
+        .unwrap();
+
+    let fee_recipient = env.fee_recipient;
+    let mut ix = instruction::claim_token_prize(
+        &program_id(),
+        &winner.pubkey(),
+        &room.address,
+        &room.players[0].pubkey(),
+        &fee_recipient,
+        &room.mint,
+        &room.token_account(1),
+    );
+    ix.accounts[8].pubkey = room.token_account(0);
+    let result = env.process(&[ix], &[winner]).await;
+    assert_uno_error(result, UnoError::InvalidPlatformWallet);
+}
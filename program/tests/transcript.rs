@@ -0,0 +1,155 @@
+// Testy rozliczania gry na podstawie zapisu ruchów odtwarzanego on-chain
+
+mod common;
+
+use common::*;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use uno_solana::{
+    error::UnoError,
+    rules::{shuffled_deck, Color, GameState, Move},
+    GameStatus,
+};
+
+const DECK_SEED: [u8; 32] = [42u8; 32];
+
+struct Game {
+    players: Vec<Keypair>,
+    address: Pubkey,
+}
+
+/// Rozpoczęta gra `player_count` graczy z ustalonym ziarnem talii
+async fn started_game(env: &mut TestEnv, player_count: u8) -> Game {
+    let (address, players) = room_with_players(env, player_count, player_count as usize - 1).await;
+    let signers: Vec<&Keypair> = players.iter().collect();
+    env.prepare_start(&address, &signers).await;
+    env.process(&[start_game_ix(&players[0].pubkey(), &address, "game-1")], &[&players[0]]).await.unwrap();
+
+    let mut room = env.room(&address).await;
+    assert_ne!(room.deck_seed, [0u8; 32]);
+    room.deck_seed = DECK_SEED;
+    env.set_room(&address, &room).await;
+
+    Game { players, address }
+}
+
+/// Rozgrywa grę zachłannie (pierwsza pasująca karta, inaczej dobranie) i zwraca zapis ruchów
+fn greedy_transcript(player_count: usize, deck_seed: &[u8; 32]) -> (Vec<Move>, usize) {
+    let mut state = GameState::new(player_count, shuffled_deck(deck_seed)).unwrap();
+    let mut moves = Vec::new();
+
+    while state.winner.is_none() {
+        let hand = &state.hands[state.current_player];
+        let game_move = match hand.iter().position(|card| card.can_play_on(&state.top_card)) {
+            Some(index) => Move::Play {
+                card_index: index as u8,
+                chosen_color: hand[index].is_wild().then_some(Color::Green),
+            },
+            None => Move::Draw,
+        };
+        state.apply(game_move).expect("greedy move is legal");
+        moves.push(game_move);
+    }
+    (moves, state.winner.unwrap())
+}
+
+#[tokio::test]
+async fn settle_replays_transcript_and_records_winner() {
+    let mut env = TestEnv::start(program_test()).await;
+    let game = started_game(&mut env, 2).await;
+    let (moves, winner_index) = greedy_transcript(2, &DECK_SEED);
+
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[settle_ix(&authority.pubkey(), &game.address, &moves, &[])], &[&authority])
+        .await
+        .unwrap();
+
+    let room = env.room(&game.address).await;
+    assert_eq!(room.status, GameStatus::Completed);
+    assert_eq!(room.winner, Some(game.players[winner_index].pubkey()));
+    assert_eq!(room.placements[0], game.players[winner_index].pubkey());
+    assert_eq!(room.placements.len(), 2);
+    assert!(room.game_ended_at.is_some());
+}
+
+#[tokio::test]
+async fn settle_accepts_transcript_signed_by_all_players() {
+    let mut env = TestEnv::start(program_test()).await;
+    let game = started_game(&mut env, 2).await;
+    let (moves, winner_index) = greedy_transcript(2, &DECK_SEED);
+
+    let [first, second] = [&game.players[0], &game.players[1]];
+    let ix = settle_ix(&first.pubkey(), &game.address, &moves, &[second.pubkey()]);
+    env.process(&[ix], &[first, second]).await.unwrap();
+
+    let room = env.room(&game.address).await;
+    assert_eq!(room.winner, Some(game.players[winner_index].pubkey()));
+}
+
+#[tokio::test]
+async fn settle_rejects_transcript_without_all_player_signatures() {
+    let mut env = TestEnv::start(program_test()).await;
+    let game = started_game(&mut env, 2).await;
+    let (moves, _) = greedy_transcript(2, &DECK_SEED);
+
+    let player = &game.players[0];
+    let result = env.process(&[settle_ix(&player.pubkey(), &game.address, &moves, &[])], &[player]).await;
+
+    assert_uno_error(result, UnoError::ResultNotApproved);
+}
+
+#[tokio::test]
+async fn settle_rejects_transcript_without_winner() {
+    let mut env = TestEnv::start(program_test()).await;
+    let game = started_game(&mut env, 2).await;
+    let (mut moves, _) = greedy_transcript(2, &DECK_SEED);
+    moves.pop();
+
+    let authority = env.result_authority.insecure_clone();
+    let result = env.process(&[settle_ix(&authority.pubkey(), &game.address, &moves, &[])], &[&authority]).await;
+
+    assert_uno_error(result, UnoError::TranscriptIncomplete);
+    assert_eq!(env.room(&game.address).await.status, GameStatus::InProgress);
+}
+
+#[tokio::test]
+async fn settle_rejects_illegal_or_trailing_moves() {
+    let mut env = TestEnv::start(program_test()).await;
+    let game = started_game(&mut env, 2).await;
+    let (moves, _) = greedy_transcript(2, &DECK_SEED);
+    let authority = env.result_authority.insecure_clone();
+
+    // Karta spoza ręki gracza
+    let mut forged = moves.clone();
+    forged[0] = Move::Play { card_index: 200, chosen_color: None };
+    let result = env.process(&[settle_ix(&authority.pubkey(), &game.address, &forged, &[])], &[&authority]).await;
+    assert_uno_error(result, UnoError::InvalidTranscript);
+
+    // Ruch po zakończeniu gry
+    let mut trailing = moves;
+    trailing.push(Move::Draw);
+    let result = env.process(&[settle_ix(&authority.pubkey(), &game.address, &trailing, &[])], &[&authority]).await;
+    assert_uno_error(result, UnoError::InvalidTranscript);
+}
+
+#[tokio::test]
+async fn settle_replays_only_players_who_have_not_forfeited() {
+    let mut env = TestEnv::start(program_test()).await;
+    let game = started_game(&mut env, 3).await;
+    let forfeited = &game.players[1];
+    env.process(&[forfeit_ix(forfeited, &game.address)], &[forfeited]).await.unwrap();
+
+    // Zapis dwóch pozostałych graczy - miejsca przy stole w kolejności z `players`
+    let seats = [game.players[0].pubkey(), game.players[2].pubkey()];
+    let (moves, winner_index) = greedy_transcript(2, &DECK_SEED);
+
+    let authority = env.result_authority.insecure_clone();
+    env.process(&[settle_ix(&authority.pubkey(), &game.address, &moves, &[])], &[&authority])
+        .await
+        .unwrap();
+
+    let room = env.room(&game.address).await;
+    assert_eq!(room.status, GameStatus::Completed);
+    assert_eq!(room.winner, Some(seats[winner_index]));
+    assert_eq!(room.placements, vec![seats[winner_index], seats[1 - winner_index], forfeited.pubkey()]);
+}